@@ -0,0 +1,499 @@
+//! Function contract attributes for the [`assume`] crate.
+//!
+//! These are re-exported by `assume` under its `contracts` feature, which is how they are meant
+//! to be used. The expansions refer to `::assume`, so it must be a dependency under that name.
+//!
+//! [`assume`]: https://docs.rs/assume
+#![doc(html_root_url = "https://docs.rs/assume-macros/0.5.0")]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use std::mem;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_macro_input, parse_quote, parse_quote_spanned, Block, DeriveInput, Expr, ExprBreak,
+    ExprClosure, Item, ItemFn, Lifetime, LitStr, Macro, ReturnType, Stmt, Token,
+};
+
+/// Assumes that the given condition holds on entry to the function.
+///
+/// This is `assume!` of the condition as the first statement of the function body, so it may
+/// refer to the arguments. In checked configurations a violation panics with the condition and
+/// the name of the function. The attribute may be repeated, one condition each.
+///
+/// ```
+/// # extern crate assume;
+/// use assume::requires;
+///
+/// #[requires(index < 16)]
+/// fn lookup(table: &[u8; 16], index: usize) -> u8 {
+///     table[index]  // Bounds check optimized out per assumption.
+/// }
+/// # fn main() { assert_eq!(lookup(&[0; 16], 3), 0); }
+/// ```
+#[proc_macro_attribute]
+pub fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut function = parse_macro_input!(item as ItemFn);
+    let clauses = match take_clauses(attr.into(), "requires", &mut function, precondition) {
+        Ok(clauses) => clauses,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let message = clause("precondition", &function);
+    let checks = clauses.into_iter().map(|condition| -> Stmt {
+        parse_quote! {
+            ::assume::assume!(unsafe: #condition, #message);
+        }
+    });
+    function.block.stmts.splice(0..0, checks);
+
+    function.into_token_stream().into()
+}
+
+/// Assumes that the given condition holds on the return value of the function.
+///
+/// Written as a closure of one argument, which is bound to a reference to the return value. The
+/// body runs as usual (an early `return` or `?` included), then the condition is `assume!`d and
+/// the value is returned. When the function is inlined, the optimizer may rely on the condition
+/// in the caller as well. In checked configurations a violation panics with the condition and
+/// the name of the function. The attribute may be repeated, one condition each.
+///
+/// The condition may also refer to the arguments, as long as the body does not move them. Not
+/// supported on `async fn` or `const fn`.
+///
+/// The body stays in the function, as a labeled block that its `return`s break out of, so it
+/// may return borrows of its arguments as usual. `?` is supported on the types of the standard
+/// library that it applies to on stable (`Result`, `Option`, `ControlFlow` and `Poll` of
+/// those), not on others that implement the unstable `Try`. Within a macro, `return` and `?`
+/// are only supported in arguments that are comma-separated expressions, or `[value; n]` as in
+/// `vec!`. Other uses of either are rejected with an error naming the limitation.
+///
+/// ```compile_fail
+/// # extern crate assume;
+/// use assume::ensures;
+///
+/// macro_rules! first_of {
+///     ($first:expr => $second:expr) => {
+///         $first
+///     };
+/// }
+///
+/// #[ensures(|ret| ret.is_some())]
+/// fn first(value: Option<u32>) -> Option<u32> {
+///     Some(first_of!(value? => 0))
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// # extern crate assume;
+/// use assume::ensures;
+///
+/// #[inline]
+/// #[ensures(|index| *index < 12)]
+/// fn month_index(month: &str) -> usize {
+///     /* ... */
+///     # 0
+/// }
+///
+/// fn days_in(table: &[u8; 12], month: &str) -> u8 {
+///     table[month_index(month)]  // Bounds check optimized out per assumption.
+/// }
+/// # fn main() { assert_eq!(days_in(&[31; 12], "jan"), 31); }
+/// ```
+#[proc_macro_attribute]
+pub fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut function = parse_macro_input!(item as ItemFn);
+    let clauses = match take_clauses(attr.into(), "ensures", &mut function, postcondition) {
+        Ok(clauses) => clauses,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    if let Some(asyncness) = function.sig.asyncness {
+        return error(&asyncness, "postconditions are not supported on `async fn`");
+    }
+    if let Some(constness) = function.sig.constness {
+        return error(&constness, "postconditions are not supported on `const fn`");
+    }
+
+    // Not nameable by the conditions or the body.
+    let ret = Ident::new("ret", Span::mixed_site());
+    let message = clause("postcondition", &function);
+    let annotation = return_annotation(&function.sig.output);
+
+    let body = match exits_to_block(&mut function.block) {
+        Ok(body) => body,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    function.block = parse_quote! {{
+        let #ret #annotation = #body;
+        #(::assume::__assume_ensures!(#ret, #message, #clauses);)*
+        #ret
+    }};
+
+    function.into_token_stream().into()
+}
+
+/// Derives `assume::Invariant` from `#[invariant(...)]` attributes.
+///
+/// Each attribute is a condition of `self`, and the invariant holds when all of them do. A
+/// checked failure of `assume_invariant!` names the first clause that does not hold.
+///
+/// ```
+/// # extern crate assume;
+/// use assume::Invariant;
+///
+/// #[derive(Invariant)]
+/// #[invariant(self.evens.iter().all(|&i| i < self.values.len()))]
+/// #[invariant(self.evens.len() <= self.values.len())]
+/// pub struct ValuesWithEvens {
+///     values: Vec<u32>,
+///     evens: Vec<usize>,
+/// }
+/// # fn main() {
+/// # let vwe = ValuesWithEvens { values: vec![1, 2], evens: vec![1] };
+/// # assert!(vwe.holds());
+/// # }
+/// ```
+#[proc_macro_derive(Invariant, attributes(invariant))]
+pub fn derive_invariant(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    let mut clauses = Vec::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("invariant") {
+            let tokens = match attr.meta.require_list() {
+                Ok(list) => list.tokens.clone(),
+                Err(error) => return error.to_compile_error().into(),
+            };
+            if let Err(error) = precondition(tokens.clone()) {
+                return error.to_compile_error().into();
+            }
+            clauses.push(tokens);
+        }
+    }
+    let holds = if clauses.is_empty() {
+        quote!(true)
+    } else {
+        quote!(#((#clauses))&&*)
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let expanded = quote! {
+        impl #impl_generics ::assume::Invariant for #name #ty_generics #where_clause {
+            fn holds(&self) -> bool {
+                #holds
+            }
+
+            fn __assume_failing_clause(&self) -> ::assume::__private::Option<&'static str> {
+                #(
+                    if !(#clauses) {
+                        return ::assume::__private::Some(
+                            ::assume::__private::stringify!(#clauses),
+                        );
+                    }
+                )*
+                ::assume::__private::None
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Assumes the invariant of `self` on entry to the method, and checks it on exit.
+///
+/// This is `assume_invariant!(unsafe: self)` as the first statement of the method and again on
+/// its return value's way out, so that the body may rely on the invariant and the method is
+/// held to restoring it. In checked configurations a violation panics, naming the failing
+/// clause, whether the invariant was broken on entry or exit, and the method.
+///
+/// Only for methods taking `&self` or `&mut self`, whose return value does not borrow from
+/// `self`. Not supported on `async fn` or `const fn`. The body is kept as with [`ensures`].
+///
+/// [`ensures`]: macro@ensures
+///
+/// ```
+/// # extern crate assume;
+/// use assume::{maintains_invariant, Invariant};
+///
+/// #[derive(Invariant)]
+/// #[invariant(self.evens.iter().all(|&i| i < self.values.len()))]
+/// pub struct ValuesWithEvens {
+///     values: Vec<u32>,
+///     evens: Vec<usize>,
+/// }
+///
+/// impl ValuesWithEvens {
+///     #[maintains_invariant]
+///     pub fn push(&mut self, value: u32) {
+///         if value.is_multiple_of(2) {
+///             self.evens.push(self.values.len());
+///         }
+///         self.values.push(value);
+///     }
+/// }
+/// # fn main() {
+/// # let mut vwe = ValuesWithEvens { values: vec![], evens: vec![] };
+/// # vwe.push(2);
+/// # assert!(vwe.holds());
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn maintains_invariant(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    if !attr.is_empty() {
+        return error(
+            &TokenStream2::from(attr),
+            "`maintains_invariant` takes no arguments",
+        );
+    }
+    match function.sig.receiver() {
+        Some(receiver) if receiver.reference.is_some() => {}
+        _ => {
+            return error(
+                &function.sig,
+                "`maintains_invariant` requires a `&self` or `&mut self` method",
+            )
+        }
+    }
+    if let Some(asyncness) = function.sig.asyncness {
+        return error(
+            &asyncness,
+            "`maintains_invariant` is not supported on `async fn`",
+        );
+    }
+    if let Some(constness) = function.sig.constness {
+        return error(
+            &constness,
+            "`maintains_invariant` is not supported on `const fn`",
+        );
+    }
+
+    let ident = &function.sig.ident;
+    let entry = LitStr::new(&format!("on entry to `{}`", ident), Span::call_site());
+    let exit = LitStr::new(&format!("on exit from `{}`", ident), Span::call_site());
+
+    let ret = Ident::new("ret", Span::mixed_site());
+    let annotation = return_annotation(&function.sig.output);
+    let body = match exits_to_block(&mut function.block) {
+        Ok(body) => body,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    function.block = parse_quote! {{
+        ::assume::assume_invariant!(unsafe: self, #entry);
+        let #ret #annotation = #body;
+        ::assume::assume_invariant!(unsafe: self, #exit);
+        #ret
+    }};
+
+    function.into_token_stream().into()
+}
+
+/// Removes the remaining clauses of the given kind from the function, returning them after the
+/// given one in source order.
+///
+/// Clauses are validated but passed on untouched: the compiler only remembers how they were
+/// spaced (e.g. `*ret`, not `* ret`) in streams that were never taken apart, and their text is
+/// what a failure reports.
+fn take_clauses(
+    first: TokenStream2,
+    kind: &str,
+    function: &mut ItemFn,
+    validate: fn(TokenStream2) -> syn::Result<()>,
+) -> syn::Result<Vec<TokenStream2>> {
+    let mut clauses = vec![first];
+    let mut attrs = Vec::new();
+    for attr in function.attrs.drain(..) {
+        let path = attr.path();
+        let is_clause = path.is_ident(kind)
+            || (path.segments.len() == 2
+                && path.segments[0].ident == "assume"
+                && path.segments[1].ident == kind);
+        if is_clause {
+            clauses.push(attr.meta.require_list()?.tokens.clone());
+        } else {
+            attrs.push(attr);
+        }
+    }
+    function.attrs = attrs;
+
+    for clause in &clauses {
+        validate(clause.clone())?;
+    }
+    Ok(clauses)
+}
+
+/// Turns the body of a function into a block that evaluates to its return value, so that code
+/// may follow it.
+///
+/// Each `return` and `?` of the function becomes a `break` out of the block, which is labeled
+/// if there are any. Those of nested closures, `async` blocks and items are their own. Within
+/// a macro, arguments that are comma-separated expressions, or `[value; n]`, are rewritten as
+/// well, and others may not contain either.
+fn exits_to_block(body: &mut Block) -> syn::Result<Expr> {
+    let mut exits = Exits {
+        label: Lifetime {
+            apostrophe: Span::mixed_site(),
+            ident: Ident::new("body", Span::mixed_site()),
+        },
+        used: false,
+        error: None,
+    };
+    exits.visit_block_mut(body);
+    if let Some(error) = exits.error {
+        return Err(error);
+    }
+
+    let label = &exits.label;
+    Ok(if exits.used {
+        parse_quote!(#label: #body)
+    } else {
+        parse_quote!(#body)
+    })
+}
+
+struct Exits {
+    label: Lifetime,
+    used: bool,
+    error: Option<syn::Error>,
+}
+
+impl VisitMut for Exits {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Closure(_) | Expr::Async(_) = *expr {
+            return;
+        }
+        visit_mut::visit_expr_mut(self, expr);
+
+        let label = &self.label;
+        let exit: Expr = match *expr {
+            // Built rather than parsed, as syn takes `break 'label ::path` for a labeled loop.
+            Expr::Return(ref mut ret) => Expr::Break(ExprBreak {
+                attrs: mem::take(&mut ret.attrs),
+                break_token: Token![break](ret.return_token.span),
+                label: Some(label.clone()),
+                expr: ret.expr.take(),
+            }),
+            Expr::Try(ref try_) => {
+                let (attrs, value) = (&try_.attrs, &try_.expr);
+                parse_quote_spanned! {try_.question_token.span=>
+                    #(#attrs)*
+                    match ::assume::__private::Try::branch(#value) {
+                        ::assume::__private::ControlFlow::Continue(value) => value,
+                        ::assume::__private::ControlFlow::Break(residual) => {
+                            break #label <_ as ::assume::__private::FromResidual<_>>::from_residual(
+                                residual,
+                            )
+                        }
+                    }
+                }
+            }
+            _ => return,
+        };
+        *expr = exit;
+        self.used = true;
+    }
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if mac.path.is_ident("macro_rules") || !exits_in(mac.tokens.clone()) {
+            return;
+        }
+        let list = Punctuated::<Expr, Token![,]>::parse_terminated;
+        let repeat = |input: ParseStream| -> syn::Result<(Expr, Token![;], Expr)> {
+            Ok((input.parse()?, input.parse()?, input.parse()?))
+        };
+        if let Ok(mut args) = list.parse2(mac.tokens.clone()) {
+            for arg in args.iter_mut() {
+                self.visit_expr_mut(arg);
+            }
+            mac.tokens = args.into_token_stream();
+        } else if let Ok((mut value, semi, mut len)) = repeat.parse2(mac.tokens.clone()) {
+            self.visit_expr_mut(&mut value);
+            self.visit_expr_mut(&mut len);
+            mac.tokens = quote!(#value #semi #len);
+        } else {
+            self.error.get_or_insert_with(|| {
+                syn::Error::new_spanned(
+                    &*mac,
+                    "`return` and `?` are only supported in macro arguments that are \
+                     comma-separated expressions, or `[value; n]` as in `vec!`, in functions \
+                     with postconditions or invariants",
+                )
+            });
+        }
+    }
+}
+
+/// Whether the tokens of a macro invocation may contain a `return` or `?`.
+fn exits_in(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "return",
+        TokenTree::Punct(punct) => punct.as_char() == '?',
+        TokenTree::Group(group) => exits_in(group.stream()),
+        TokenTree::Literal(_) => false,
+    })
+}
+
+fn precondition(tokens: TokenStream2) -> syn::Result<()> {
+    syn::parse2::<Expr>(tokens).map(drop)
+}
+
+fn postcondition(tokens: TokenStream2) -> syn::Result<()> {
+    let closure: ExprClosure = syn::parse2(tokens)?;
+    if closure.inputs.len() == 1 {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            closure,
+            "postcondition must be a closure of the return value, like `|ret| ...`",
+        ))
+    }
+}
+
+/// The message of a failed clause, naming its kind and function.
+fn clause(kind: &str, function: &ItemFn) -> LitStr {
+    LitStr::new(
+        &format!("{} of `{}`", kind, function.sig.ident),
+        Span::call_site(),
+    )
+}
+
+/// The type annotation of a binding of the body's value.
+///
+/// This lets `?` in the body convert errors, but cannot spell `impl Trait`.
+fn return_annotation(output: &ReturnType) -> TokenStream2 {
+    match *output {
+        ReturnType::Default => quote!(: ()),
+        ReturnType::Type(_, ref ty) if !mentions_impl(ty.to_token_stream()) => quote!(: #ty),
+        ReturnType::Type(..) => quote!(),
+    }
+}
+
+fn mentions_impl(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "impl",
+        TokenTree::Group(group) => mentions_impl(group.stream()),
+        _ => false,
+    })
+}
+
+fn error<T: ToTokens>(tokens: &T, message: &str) -> TokenStream {
+    syn::Error::new_spanned(tokens, message)
+        .to_compile_error()
+        .into()
+}
@@ -0,0 +1,51 @@
+//! Selects the backend that unchecked assumptions are handed to the optimizer with, and
+//! whether this crate's own assumptions are checked.
+//!
+//! `core::hint::assert_unchecked` was stabilized in Rust 1.81. On older compilers, unchecked
+//! assumptions branch to `core::hint::unreachable_unchecked` instead. Likewise, unchecked
+//! left shifts use `unchecked_shl` from Rust 1.93, and a `checked_shl` whose `None` is
+//! unreachable before.
+//!
+//! `assume_checks` is set when assumptions made in this package, including its tests, are
+//! checked: with `always-check` or `--cfg assume_checked`, or with `debug_assertions` unless
+//! `unchecked-in-debug` or `--cfg assume_unchecked` is given. Tests of checked failures are
+//! gated on it.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    let minor = rustc_minor_version();
+    if matches!(minor, Some(minor) if minor >= 81) {
+        println!("cargo:rustc-cfg=assume_assert_unchecked");
+    }
+    if matches!(minor, Some(minor) if minor >= 93) {
+        println!("cargo:rustc-cfg=assume_unchecked_shl");
+    }
+
+    let always_check = is_set("CARGO_FEATURE_ALWAYS_CHECK") || is_set("CARGO_CFG_ASSUME_CHECKED");
+    let never_check =
+        is_set("CARGO_FEATURE_UNCHECKED_IN_DEBUG") || is_set("CARGO_CFG_ASSUME_UNCHECKED");
+    if always_check || (!never_check && is_set("CARGO_CFG_DEBUG_ASSERTIONS")) {
+        println!("cargo:rustc-cfg=assume_checks");
+    }
+}
+
+fn is_set(key: &str) -> bool {
+    env::var_os(key).is_some()
+}
+
+/// The minor version of the compiler, as in `rustc 1.81.0 (eeb90cda1 2024-09-04)`.
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let mut parts = version.strip_prefix("rustc ")?.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
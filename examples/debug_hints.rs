@@ -0,0 +1,40 @@
+//! Shows the hints of `assume!` in use in a debug build, under `unchecked-in-debug`.
+//!
+//! Inspect the generated code with:
+//!
+//! ```text
+//! cargo rustc --features unchecked-in-debug --example debug_hints -- -C opt-level=1 --emit=asm
+//! ```
+//!
+//! The dev profile has `debug_assertions` on, under which assumptions are checked by default.
+//! With the feature, they are handed to the optimizer instead, as in a release build: `get` has
+//! no bounds check and `slot` no division, while `get_checked` keeps its bounds check.
+//! `tests/debug_hints.rs` checks as much.
+
+#[macro_use]
+extern crate assume;
+
+#[inline(never)]
+#[no_mangle]
+pub fn get(values: &[u32], index: usize) -> u32 {
+    assume!(unsafe: index < values.len());
+    values[index]
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn get_checked(values: &[u32], index: usize) -> u32 {
+    values[index]
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn slot(index: usize, capacity: usize) -> usize {
+    index % assume_pow2!(unsafe: capacity)
+}
+
+fn main() {
+    let values = [1, 2, 3];
+    assert_eq!(get(&values, 2), get_checked(&values, 2));
+    assert_eq!(slot(21, 16), 5);
+}
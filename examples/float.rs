@@ -0,0 +1,79 @@
+//! Shows what the float assumptions change in the generated code, and what they do not.
+//!
+//! Inspect the generated code with:
+//!
+//! ```text
+//! cargo rustc --release --example float -- --emit=asm
+//! ```
+//!
+//! The optimizer tracks whether a float may be NaN or infinite, through arithmetic, so tests of
+//! that fold away: `sanitize` returns its argument as is, `doubled_is_nan` is `false` and `clamp`
+//! only applies its upper bound. Comparisons, `partial_cmp`, `min`, `max` and casts to integers
+//! still handle NaN at the time of writing, so `compare` keeps its `unwrap` panic and `larger`
+//! its NaN test. `assume_float_cmp!` and `assume_float_max!` leave NaN out of it: `compare_fast`
+//! has no panic, and `larger_fast` is a single `maxsd` on x86-64.
+
+#[macro_use]
+extern crate assume;
+
+use std::cmp::Ordering;
+
+#[inline(never)]
+#[no_mangle]
+pub fn sanitize(x: f64) -> f64 {
+    let x = assume_not_nan!(unsafe: x);
+    if x.is_nan() {
+        0.0
+    } else {
+        x
+    }
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn doubled_is_nan(x: f64) -> bool {
+    let x = assume_finite!(unsafe: x);
+    (x * 2.0).is_nan()
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn clamp(x: f32) -> f32 {
+    assume_float_range!(unsafe: x in 0.0..).clamp(0.0, 1.0)
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn compare(x: f64, y: f64) -> Ordering {
+    let (x, y) = (assume_not_nan!(unsafe: x), assume_not_nan!(unsafe: y));
+    x.partial_cmp(&y).unwrap()
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn compare_fast(x: f64, y: f64) -> Ordering {
+    assume_float_cmp!(unsafe: x, y)
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn larger(x: f64, y: f64) -> f64 {
+    let (x, y) = (assume_not_nan!(unsafe: x), assume_not_nan!(unsafe: y));
+    x.max(y)
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn larger_fast(x: f64, y: f64) -> f64 {
+    assume_float_max!(unsafe: x, y)
+}
+
+fn main() {
+    assert_eq!(sanitize(1.5), 1.5);
+    assert!(!doubled_is_nan(f64::MAX));
+    assert_eq!(clamp(2.0), 1.0);
+    assert_eq!(compare(1.0, 2.0), Ordering::Less);
+    assert_eq!(compare_fast(1.0, 2.0), Ordering::Less);
+    assert_eq!(larger(1.0, 2.0), 2.0);
+    assert_eq!(larger_fast(1.0, 2.0), 2.0);
+}
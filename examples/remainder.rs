@@ -0,0 +1,40 @@
+//! Shows the remainder loop of a vectorized loop going away under `assume_aligned_len!`.
+//!
+//! Inspect the generated code with:
+//!
+//! ```text
+//! cargo rustc --release --example remainder -- --emit=asm
+//! ```
+//!
+//! Both functions sum the slice with a vectorized loop over 8 elements at a time. `sum` then
+//! adds up the remaining (up to 7) elements with a scalar loop, while `sum_of_blocks` has no
+//! such loop: the length is assumed to be a multiple of 8.
+
+#[macro_use]
+extern crate assume;
+
+#[inline(never)]
+#[no_mangle]
+pub fn sum(values: &[u32]) -> u32 {
+    values.iter().fold(0, |sum, value| sum.wrapping_add(*value))
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn sum_of_blocks(values: &[u32]) -> u32 {
+    assume_aligned_len!(unsafe: values, 8, "values must come in blocks of 8");
+    values.iter().fold(0, |sum, value| sum.wrapping_add(*value))
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn slot(index: usize, capacity: usize) -> usize {
+    // Computed as `index & (capacity - 1)`, without a division.
+    index % assume_pow2!(unsafe: capacity)
+}
+
+fn main() {
+    let values: Vec<u32> = (0..64).collect();
+    assert_eq!(sum(&values), sum_of_blocks(&values));
+    assert_eq!(slot(21, 16), 5);
+}
@@ -0,0 +1,382 @@
+//! Arithmetic assumptions.
+
+/// Assumes that the given integer operation does not overflow, evaluating to its result.
+///
+/// The operation is one of `lhs + rhs`, `lhs - rhs`, `lhs * rhs` or `lhs << rhs`, where the
+/// operator is the outermost one of the expression (following the usual precedence, so
+/// `i * 4 + 3` is an addition and `i + n / 2` is an addition of `n / 2`). An expression whose
+/// outermost operator is any other, such as `a * b / c` or `a + b & c`, is rejected. Each
+/// operand is evaluated once. In checked configurations the operation is done with
+/// `checked_add` (and so on), panicking with both operands if it overflows. Otherwise, it is
+/// done with `unchecked_add` (and so on), which lets the optimizer rely on the absence of
+/// overflow.
+///
+/// The type of the left operand must be known, so an unsuffixed literal (such as the `1` of
+/// `1 << n`) needs a suffix. As with `checked_shl`, the shift amount is a `u32`, and a shift
+/// only assumes that the shift amount is less than the bit width of the integer.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_row() -> usize { 1 }
+/// # fn main() {
+/// use assume::assume_no_overflow;
+///
+/// // Some row that, per invariants, is always within the table.
+/// let row = get_row();
+///
+/// let start = assume_no_overflow!(unsafe: row * 64);
+/// let end = assume_no_overflow!(unsafe: start + 64, "row {} overflows", row);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_no_overflow {
+    (unsafe: $($tokens:tt)+) => {{
+        $crate::__assume_no_overflow!(@munch [] [] [] [] yes {$($tokens)+} $($tokens)+)
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Finds the outermost operator of the operation: the rightmost one of the lowest precedence,
+/// skipping unary operators and generic arguments.
+///
+/// Carries the operation so far, the split (the precedence level, and either the checked and
+/// unchecked methods and the left operand, or `!` and an unsupported operator), the right
+/// operand so far, the depth of generic arguments, and whether the previous token was an
+/// operator. As with `__assume_split!`, the remaining tokens are carried twice to keep the
+/// originals. Operators of lower precedence than `<<` cannot have a supported one outside
+/// them, so they are rejected as soon as they are found.
+///
+/// ```compile_fail
+/// # let (a, b, c) = (3u32, 5u32, 2u32);
+/// assume::assume_no_overflow!(unsafe: a * b / c);
+/// ```
+/// ```compile_fail
+/// # let (a, b, c) = (3u32, 5u32, 2u32);
+/// assume::assume_no_overflow!(unsafe: a * b % c);
+/// ```
+/// ```compile_fail
+/// # let (a, b, c) = (3u32, 5u32, 2u32);
+/// assume::assume_no_overflow!(unsafe: a + b & c);
+/// ```
+/// ```compile_fail
+/// # let (a, b) = (3u32, 5u32);
+/// assume::assume_no_overflow!(unsafe: a + b >> 1);
+/// ```
+/// ```compile_fail
+/// # let (a, b, c) = (3u32, 5u32, 2u32);
+/// assume::assume_no_overflow!(unsafe: a << b >> c);
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_no_overflow {
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {, $($m:tt)*} , $($msg:tt)*) => {
+        $crate::__assume_no_overflow!(@done (, $($msg)*) $all $split $right)
+    };
+    (@munch $all:tt $split:tt $right:tt $depth:tt $prev:ident {}) => {
+        $crate::__assume_no_overflow!(@done () $all $split $right)
+    };
+
+    // Generic arguments, of a turbofish or a qualified path.
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [$($depth:tt)+] $prev:ident {< $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [$($depth)+ <] no {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [$_:tt $($depth:tt)*] $prev:ident {> $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [$($depth)*] no {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [$_:tt $__:tt $($depth:tt)*] $prev:ident {>> $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [$($depth)*] no {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [$($depth:tt)+] $prev:ident {$_:tt $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [$($depth)+] no {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] $prev:ident {:: < $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $a $b] $split [$($right)* $a $b] [<] no {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] yes {< $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [<] no {$($m)*} $($rest)*)
+    };
+
+    // Unary operators.
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] yes {- $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] yes {* $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] yes {& $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] yes {&& $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] $prev:ident {! $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] $prev:ident {as $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+
+    // Multiplicative operators, which only split an operation with no additive or shift one.
+    (@munch [$($all:tt)*] [add $($s:tt)*] [$($right:tt)*] [] no {* $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [add $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] [shift $($s:tt)*] [$($right:tt)*] [] no {* $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {* $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(
+            @munch [$($all)* $t] [mul checked_mul unchecked_mul [$($all)*]] [] [] yes {$($m)*} $($rest)*
+        )
+    };
+    (@munch [$($all:tt)*] [add $($s:tt)*] [$($right:tt)*] [] no {/ $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [add $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] [shift $($s:tt)*] [$($right:tt)*] [] no {/ $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {/ $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [mul ! $t] [] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] [add $($s:tt)*] [$($right:tt)*] [] no {% $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [add $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] [shift $($s:tt)*] [$($right:tt)*] [] no {% $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {% $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [mul ! $t] [] [] yes {$($m)*} $($rest)*)
+    };
+
+    // Additive operators, which only split an operation with no shift one.
+    (@munch [$($all:tt)*] [shift $($s:tt)*] [$($right:tt)*] [] no {+ $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {+ $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(
+            @munch [$($all)* $t] [add checked_add unchecked_add [$($all)*]] [] [] yes {$($m)*} $($rest)*
+        )
+    };
+    (@munch [$($all:tt)*] [shift $($s:tt)*] [$($right:tt)*] [] no {- $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift $($s)*] [$($right)* $t] [] yes {$($m)*} $($rest)*)
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {- $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(
+            @munch [$($all)* $t] [add checked_sub unchecked_sub [$($all)*]] [] [] yes {$($m)*} $($rest)*
+        )
+    };
+
+    // Shift operators, which always split.
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {<< $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(
+            @munch [$($all)* $t] [shift checked_shl unchecked_shl [$($all)*]] [] [] yes {$($m)*} $($rest)*
+        )
+    };
+    (@munch [$($all:tt)*] $split:tt $right:tt [] no {>> $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] [shift ! $t] [] [] yes {$($m)*} $($rest)*)
+    };
+
+    // Binary operators of lower precedence.
+    (@munch $all:tt $split:tt $right:tt [] no {& $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {^ $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {| $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {== $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {!= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {< $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {> $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {<= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {>= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] no {&& $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {|| $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {.. $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {..= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@munch $all:tt $split:tt $right:tt [] $prev:ident {= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+
+    // Operands.
+    (@munch [$($all:tt)*] $split:tt [$($right:tt)*] [] $prev:ident {$_:tt $($m:tt)*} $t:tt $($rest:tt)*) => {
+        $crate::__assume_no_overflow!(@munch [$($all)* $t] $split [$($right)* $t] [] no {$($m)*} $($rest)*)
+    };
+
+    (@done $msg:tt $all:tt [] $right:tt) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@done $msg:tt $all:tt [$level:ident ! $op:tt] $right:tt) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@done ($(,)?) $all:tt $split:tt $right:tt) => {
+        $crate::__assume_no_overflow!(@impl $all $split $right, "", "")
+    };
+    (@done (, $msg:expr $(,)?) $all:tt $split:tt $right:tt) => {
+        $crate::__assume_no_overflow!(@impl $all $split $right, ": ", $msg)
+    };
+    (@done (, $fmt:expr, $($args:tt)*) $all:tt $split:tt $right:tt) => {
+        $crate::__assume_no_overflow!(
+            @impl $all $split $right, ": ", $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    (
+        @impl [$($all:tt)*] [$level:ident $checked:ident $unchecked:ident [$($left:tt)+]] [$($right:tt)+],
+        $separator:expr,
+        $msg:expr
+    ) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($($left)+, $($right)+) } {
+            (left, right) => {
+                if $crate::__assume_checked!() {
+                    match left.$checked(right) {
+                        $crate::__private::Some(value) => value,
+                        $crate::__private::None => $crate::__assume_impl!(
+                            @fail $crate::__private::concat!(
+                                $crate::__private::stringify!($($all)*),
+                                " does not overflow"
+                            ),
+                            "assumption failed: {} does not overflow{}{}\n   left: {:?}\n  right: {:?}",
+                            $crate::__private::stringify!($($all)*),
+                            $separator,
+                            $msg,
+                            left,
+                            right,
+                        ),
+                    }
+                } else {
+                    unsafe { $crate::__assume_no_overflow!(@unchecked left $unchecked right) }
+                }
+            }
+        }
+    };
+    (@impl $($_:tt)*) => {
+        $crate::__assume_no_overflow!(@unsupported)
+    };
+    (@unchecked $left:ident unchecked_shl $right:ident) => {
+        $crate::__assume_unchecked_shl!($left, $right)
+    };
+    (@unchecked $left:ident $unchecked:ident $right:ident) => {
+        $left.$unchecked($right)
+    };
+    (@unsupported) => {
+        $crate::__private::compile_error!(
+            "assumption must be an addition, subtraction, multiplication or left shift"
+        )
+    };
+}
+
+/// Shifts left, assuming that the shift amount is less than the bit width.
+///
+/// Uses `unchecked_shl` where the compiler has it (see `build.rs`).
+#[cfg(assume_unchecked_shl)]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_unchecked_shl {
+    ($left:ident, $right:ident) => {
+        $left.unchecked_shl($right)
+    };
+}
+
+#[cfg(not(assume_unchecked_shl))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_unchecked_shl {
+    ($left:ident, $right:ident) => {
+        match $left.checked_shl($right) {
+            $crate::__private::Some(value) => value,
+            $crate::__private::None => $crate::__private::unreachable_unchecked(),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_result() {
+        let (a, b) = (6u32, 2u32);
+        assert_eq!(assume_no_overflow!(unsafe: a + b), 8);
+        assert_eq!(assume_no_overflow!(unsafe: a - b, "oh no"), 4);
+        assert_eq!(
+            assume_no_overflow!(unsafe: a * b, "oh no, a {}", "problem"),
+            12
+        );
+        assert_eq!(assume_no_overflow!(unsafe: a << b), 24);
+    }
+
+    #[test]
+    fn splits_at_the_outermost_operator() {
+        let values = [3i32, -4];
+        assert_eq!(assume_no_overflow!(unsafe: values[0] * 4 + 3), 15);
+        assert_eq!(assume_no_overflow!(unsafe: values[0] + 4 * 3), 15);
+        assert_eq!(
+            assume_no_overflow!(unsafe: 1i32 << values[0] as u32 + 1),
+            16
+        );
+        assert_eq!(assume_no_overflow!(unsafe: 10i32 - 2 - 3), 5);
+        assert_eq!(assume_no_overflow!(unsafe: -values[1] * -2), -8);
+        assert_eq!(
+            assume_no_overflow!(unsafe: values.len() as i32 - -*values.last().unwrap()),
+            -2
+        );
+    }
+
+    #[test]
+    fn binds_other_operators_tighter() {
+        let (a, b, c) = (3u32, 5u32, 2u32);
+        assert_eq!(assume_no_overflow!(unsafe: a + b / c), 5);
+        assert_eq!(assume_no_overflow!(unsafe: a * b / c + 1), 8);
+        assert_eq!(assume_no_overflow!(unsafe: a * b % c * 4), 4);
+        assert_eq!(assume_no_overflow!(unsafe: a / c * b), 5);
+        assert_eq!(assume_no_overflow!(unsafe: ((a + b) & c) + 1), 1);
+        assert_eq!(assume_no_overflow!(unsafe: a << (b >> c)), 6);
+        assert_eq!(assume_no_overflow!(unsafe: (a + b * c) >> 1 << 2), 24);
+        assert_eq!(assume_no_overflow!(unsafe: a << b >> c << 1), 48);
+        assert_eq!(
+            assume_no_overflow!(unsafe: ::std::cmp::max::<u32>(a, b) * *[c].first().unwrap()),
+            10
+        );
+        assert_eq!(assume_no_overflow!(unsafe: <u32>::min(a, b) * !!c), 6);
+    }
+
+    #[test]
+    fn evaluates_operands_once() {
+        let mut calls = 0u32;
+        assume_no_overflow!(unsafe: { calls += 1; calls } + { calls += 1; calls });
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: a * 2 + b does not overflow: oh no\n   left: 254\n  right: 2"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_operands() {
+        let (a, b) = (127u8, 2u8);
+        assume_no_overflow!(unsafe: a * 2 + b, "oh no");
+    }
+}
@@ -0,0 +1,204 @@
+//! Integers that carry their bounds in their type.
+
+use core::ops::{Index, IndexMut};
+
+use refined::{InRange, Refined};
+
+/// An integer of type `T` from `MIN` to `MAX`, inclusive.
+///
+/// This is a [`Refined`] value, so every read assumes that `MIN <= value && value <= MAX`. It is
+/// created with `Bounded::new`, which checks the bounds, or `Bounded::new_unchecked`, which
+/// assumes them. Unsigned bounds of `usize` index arrays without a bounds check, and
+/// [`wrapping_add`] and [`wrapping_sub`] do arithmetic that stays within the bounds.
+///
+/// [`wrapping_add`]: Refined::wrapping_add
+/// [`wrapping_sub`]: Refined::wrapping_sub
+///
+/// ```
+/// use assume::Bounded;
+///
+/// struct Ring {
+///     slots: [u32; 8],
+///     head: Bounded<usize, 0, 7>,
+/// }
+///
+/// impl Ring {
+///     fn push(&mut self, value: u32) {
+///         self.slots[self.head] = value;  // No bounds check.
+///         self.head = self.head.wrapping_add(1);
+///     }
+/// }
+///
+/// let mut ring = Ring { slots: [0; 8], head: Bounded::new(7).unwrap() };
+/// ring.push(1);
+/// ring.push(2);
+/// assert_eq!((ring.slots[7], ring.slots[0], *ring.head), (1, 2, 1));
+/// ```
+pub type Bounded<T, const MIN: i128, const MAX: i128> = Refined<T, InRange<MIN, MAX>>;
+
+/// Fails to compile unless `MIN` and `MAX` are bounds of some values of the given type.
+macro_rules! assert_bounds {
+    ($ty:ty) => {
+        const {
+            assert!(
+                <$ty>::MIN as i128 <= MIN && MIN <= MAX && MAX <= <$ty>::MAX as i128,
+                "bounds must be ordered and within the range of the type"
+            )
+        }
+    };
+}
+
+macro_rules! bounded_arithmetic {
+    ($($ty:ty => $unsigned:ty)*) => {$(
+        impl<const MIN: i128, const MAX: i128> Bounded<$ty, MIN, MAX> {
+            /// Adds to the value, wrapping around from `MAX` to `MIN`.
+            ///
+            /// With a `MIN` of `0` this is addition modulo `MAX + 1`.
+            #[inline]
+            pub fn wrapping_add(self, rhs: $unsigned) -> Self {
+                let (offset, last) = Self::offset(self.into_inner());
+                let rhs = Self::reduce(rhs, last);
+                let offset = if rhs > last - offset {
+                    rhs - (last - offset) - 1
+                } else {
+                    offset + rhs
+                };
+                Self::from_offset(offset)
+            }
+
+            /// Subtracts from the value, wrapping around from `MIN` to `MAX`.
+            #[inline]
+            pub fn wrapping_sub(self, rhs: $unsigned) -> Self {
+                let (offset, last) = Self::offset(self.into_inner());
+                let rhs = Self::reduce(rhs, last);
+                let offset = if rhs > offset {
+                    last - (rhs - offset - 1)
+                } else {
+                    offset - rhs
+                };
+                Self::from_offset(offset)
+            }
+
+            /// Returns the distance of the value from `MIN`, and of `MAX` from `MIN`.
+            #[inline(always)]
+            fn offset(value: $ty) -> ($unsigned, $unsigned) {
+                assert_bounds!($ty);
+                let min = MIN as $ty as $unsigned;
+                (
+                    (value as $unsigned).wrapping_sub(min),
+                    (MAX as $ty as $unsigned).wrapping_sub(min),
+                )
+            }
+
+            /// Reduces a distance modulo the number of values.
+            #[inline(always)]
+            fn reduce(distance: $unsigned, last: $unsigned) -> $unsigned {
+                if last == <$unsigned>::MAX {
+                    distance
+                } else {
+                    distance % (last + 1)
+                }
+            }
+
+            #[inline(always)]
+            fn from_offset(offset: $unsigned) -> Self {
+                let value = (MIN as $ty as $unsigned).wrapping_add(offset) as $ty;
+                // Safe, as the offset is at most `MAX - MIN`.
+                unsafe { Self::new_unchecked(value) }
+            }
+        }
+    )*};
+}
+
+bounded_arithmetic! {
+    i8 => u8
+    i16 => u16
+    i32 => u32
+    i64 => u64
+    i128 => u128
+    isize => usize
+    u8 => u8
+    u16 => u16
+    u32 => u32
+    u64 => u64
+    usize => usize
+}
+
+/// Indexes an array with a `Bounded<usize, MIN, MAX>`, without a bounds check.
+///
+/// Fails to compile unless `0 <= MIN` and `MAX < N`.
+impl<T, const N: usize, const MIN: i128, const MAX: i128> Index<Bounded<usize, MIN, MAX>>
+    for [T; N]
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Bounded<usize, MIN, MAX>) -> &T {
+        assert_in_bounds::<N, MIN, MAX>();
+        // Safe, as the index is at most `MAX`.
+        unsafe { self.get_unchecked(index.into_inner()) }
+    }
+}
+
+/// Indexes an array with a `Bounded<usize, MIN, MAX>`, without a bounds check.
+///
+/// Fails to compile unless `0 <= MIN` and `MAX < N`.
+impl<T, const N: usize, const MIN: i128, const MAX: i128> IndexMut<Bounded<usize, MIN, MAX>>
+    for [T; N]
+{
+    #[inline]
+    fn index_mut(&mut self, index: Bounded<usize, MIN, MAX>) -> &mut T {
+        assert_in_bounds::<N, MIN, MAX>();
+        // Safe, as the index is at most `MAX`.
+        unsafe { self.get_unchecked_mut(index.into_inner()) }
+    }
+}
+
+#[inline(always)]
+fn assert_in_bounds<const N: usize, const MIN: i128, const MAX: i128>() {
+    const {
+        assert!(
+            0 <= MIN && MAX < N as i128,
+            "bounds must be within the array"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Bounded;
+
+    #[test]
+    fn wraps_within_bounds() {
+        let index: Bounded<usize, 0, 7> = Bounded::new(6).unwrap();
+        assert_eq!(*index.wrapping_add(1), 7);
+        assert_eq!(*index.wrapping_add(2), 0);
+        assert_eq!(*index.wrapping_add(17), 7);
+        assert_eq!(*index.wrapping_sub(7), 7);
+        assert_eq!(*index.wrapping_add(usize::MAX), 5);
+
+        let offset: Bounded<i8, -3, 3> = Bounded::new(-2).unwrap();
+        assert_eq!(*offset.wrapping_add(5), 3);
+        assert_eq!(*offset.wrapping_add(6), -3);
+        assert_eq!(*offset.wrapping_sub(2), 3);
+        assert_eq!(*offset.wrapping_sub(255), 2);
+
+        let byte: Bounded<u8, 0, 255> = Bounded::new(250).unwrap();
+        assert_eq!(*byte.wrapping_add(10), 4);
+        assert_eq!(*byte.wrapping_sub(251), 255);
+
+        let full: Bounded<i64, { i64::MIN as i128 }, { i64::MAX as i128 }> =
+            Bounded::new(i64::MAX).unwrap();
+        assert_eq!(*full.wrapping_add(1), i64::MIN);
+    }
+
+    #[test]
+    fn indexes_arrays() {
+        let mut values = [10, 20, 30, 40];
+        let index: Bounded<usize, 1, 3> = Bounded::new(3).unwrap();
+        assert_eq!(values[index], 40);
+
+        values[index.wrapping_add(1)] = 0;
+        assert_eq!(values, [10, 0, 30, 40]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Indices that are proven in bounds by their type.
+
+use core::marker::PhantomData;
+use core::ops;
+
+/// Makes `'id` invariant, so that it cannot be shortened or lengthened to match another brand.
+type Invariant<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// Calls the given function with the slice, branded with a lifetime unique to this call.
+///
+/// An [`Index`] of the brand can only be made by the [`Branded`] slice, in bounds of it, so
+/// indexing with one needs no bounds check. As the brand is unique, an index cannot be used
+/// with any other slice: the type checker rejects it, rather than an assumption checked
+/// against the wrong slice going unnoticed.
+///
+/// ```
+/// let values = [1, 2, 3, 4];
+/// let evens = [1usize, 3];
+///
+/// let sum = assume::brand(&values, |values| {
+///     evens
+///         .iter()
+///         .filter_map(|&i| values.check(i))
+///         .map(|i| values[i])  // No bounds check.
+///         .sum::<u32>()
+/// });
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// Indices of one slice do not index another:
+///
+/// ```compile_fail
+/// let (a, b) = ([1, 2, 3], [4]);
+///
+/// assume::brand(&a, |a| {
+///     assume::brand(&b, |b| {
+///         let i = a.check(2).unwrap();
+///         b[i]
+///     })
+/// });
+/// ```
+#[inline]
+pub fn brand<T, R, F>(slice: &[T], f: F) -> R
+where
+    F: for<'id> FnOnce(Branded<'id, T>) -> R,
+{
+    f(Branded {
+        slice,
+        brand: PhantomData,
+    })
+}
+
+/// A slice branded with the lifetime `'id`, made by [`brand`].
+pub struct Branded<'id, T> {
+    slice: &'id [T],
+    brand: Invariant<'id>,
+}
+
+/// An index in bounds of the [`Branded`] slice of the brand `'id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Index<'id> {
+    index: usize,
+    brand: Invariant<'id>,
+}
+
+impl<'id, T> Branded<'id, T> {
+    /// Returns the index if it is in bounds.
+    #[inline]
+    pub fn check(&self, index: usize) -> Option<Index<'id>> {
+        if index < self.slice.len() {
+            Some(Index {
+                index,
+                brand: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index, assuming that it is in bounds.
+    ///
+    /// This is `assume!(unsafe: index < self.len())`, so in checked configurations an index
+    /// out of bounds panics.
+    ///
+    /// # Safety
+    /// The index must be less than the length of the slice.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn assume(&self, index: usize) -> Index<'id> {
+        ::assume!(unsafe: index < self.len(), "index of a branded slice");
+        Index {
+            index,
+            brand: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over every index, in order.
+    #[inline]
+    pub fn indices(&self) -> impl Iterator<Item = Index<'id>> {
+        (0..self.slice.len()).map(|index| Index {
+            index,
+            brand: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    /// Returns whether the slice has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Returns the slice.
+    #[inline]
+    pub fn as_slice(&self) -> &'id [T] {
+        self.slice
+    }
+}
+
+impl<'id, T> Clone for Branded<'id, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'id, T> Copy for Branded<'id, T> {}
+
+impl<'id, T> ops::Index<Index<'id>> for Branded<'id, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Index<'id>) -> &T {
+        // Safe, as indices of the brand are in bounds of its slice.
+        unsafe { self.slice.get_unchecked(index.index) }
+    }
+}
+
+impl<'id> Index<'id> {
+    /// Returns the index as a `usize`.
+    #[inline]
+    pub fn get(self) -> usize {
+        self.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brand;
+
+    #[test]
+    fn indexes_branded_slice() {
+        let values = [10, 20, 30];
+        brand(&values, |values| {
+            let i = values.check(2).unwrap();
+            assert_eq!(values[i], 30);
+            assert_eq!(i.get(), 2);
+            assert!(values.check(3).is_none());
+
+            let j = unsafe { values.assume(0) };
+            assert_eq!(values[j], 10);
+
+            let all: u32 = values.indices().map(|i| values[i]).sum();
+            assert_eq!(all, 60);
+        });
+    }
+
+    #[test]
+    fn returns_result() {
+        let values = [1, 2];
+        let last = brand(&values, |values| {
+            values.check(values.len() - 1).map(|i| values[i])
+        });
+        assert_eq!(last, Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: index < self.len(): index of a branded slice")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_assumed_index_out_of_bounds() {
+        let values = [1, 2];
+        brand(&values, |values| {
+            unsafe { values.assume(2) };
+        });
+    }
+}
@@ -0,0 +1,504 @@
+//! Operand capture for failed assumptions, in the style of power-assert.
+//!
+//! The condition is split at top-level `&&` or `||` into clauses, and each clause that is
+//! a single comparison is evaluated with its operands bound so that their values can be
+//! reported. Anything else is evaluated as a whole, and reported by its text alone.
+//! So are conditions of more than 32 tokens, which keeps the token walk well within the
+//! recursion limit, with a note that their operands were not captured.
+//!
+//! The `Debug`-if-available formatting of captured values is also used by other macros.
+
+use core::fmt;
+use core::mem::size_of;
+use core::str::from_utf8_unchecked;
+
+#[cfg(feature = "capture-operands")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_check {
+    // Longer conditions are reported by their text alone, with a note saying so: walking
+    // them one token at a time, as below, could run into the recursion limit.
+    (
+        $cond:expr,
+        [
+            $_0:tt $_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt $_7:tt $_8:tt $_9:tt $_10:tt $_11:tt
+            $_12:tt $_13:tt $_14:tt $_15:tt $_16:tt $_17:tt $_18:tt $_19:tt $_20:tt $_21:tt $_22:tt $_23:tt
+            $_24:tt $_25:tt $_26:tt $_27:tt $_28:tt $_29:tt $_30:tt $_31:tt
+            $($more:tt)+
+        ],
+        $fmt:literal, $($args:tt)*
+    ) => {
+        $crate::__assume_impl!(
+            $cond,
+            $crate::__private::concat!($fmt, $crate::__assume_check!(@uncaptured)),
+            $($args)*
+        )
+    };
+    (
+        $cond:expr,
+        [
+            $_0:tt $_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt $_7:tt $_8:tt $_9:tt $_10:tt $_11:tt
+            $_12:tt $_13:tt $_14:tt $_15:tt $_16:tt $_17:tt $_18:tt $_19:tt $_20:tt $_21:tt $_22:tt $_23:tt
+            $_24:tt $_25:tt $_26:tt $_27:tt $_28:tt $_29:tt $_30:tt $_31:tt
+            $($more:tt)+
+        ],
+        $($message:tt)*
+    ) => {
+        $crate::__assume_impl!(
+            $cond,
+            $crate::__private::concat!($($message)*, $crate::__assume_check!(@uncaptured))
+        )
+    };
+    (@uncaptured) => {
+        "\n  (operands not captured: the condition is longer than 32 tokens)"
+    };
+    ($cond:expr, [$($raw:tt)*], $($message:tt)*) => {{
+        if $crate::__assume_checked!() {
+            #[allow(unused_imports)]
+            use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+            #[allow(unused_unsafe)]
+            unsafe {
+                $crate::__assume_capture!(
+                    @split [$cond, $($message)*] {$($raw)*} [] [] [] [] [] {$($raw)*} $($raw)*
+                )
+            }
+        } else {
+            // Identical to the uncaptured form, so unchecked code generation is unaffected.
+            $crate::__assume_impl!($cond, $($message)*)
+        }
+    }};
+}
+
+#[cfg(feature = "capture-operands")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_capture {
+    // Splits the condition into `&&` or `||` clauses, each as `[left] [operator] [right]`
+    // for a single comparison, or `[tokens] [] []` otherwise. A second comparison, or a
+    // turbofish, whose `<` would pass for one, sticks `[tokens] [bool] []` on the clause.
+    // Mixing `&&` and `||` falls back to evaluating the condition as a whole, rather than
+    // reimplementing precedence. As in `__assume_split!`, the tokens are matched in braces
+    // and taken from a second copy, so that the clauses stringify as written.
+    (@split $f:tt $o:tt [] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {&& $($m:tt)*} $_:tt $($t:tt)+) => {
+        $crate::__assume_capture!(@split $f $o [and] [$($s)* [[$($l)+] $op $r]] [] [] [] {$($m)*} $($t)+)
+    };
+    (@split $f:tt $o:tt [and] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {&& $($m:tt)*} $_:tt $($t:tt)+) => {
+        $crate::__assume_capture!(@split $f $o [and] [$($s)* [[$($l)+] $op $r]] [] [] [] {$($m)*} $($t)+)
+    };
+    (@split $f:tt $o:tt [] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {|| $($m:tt)*} $_:tt $($t:tt)+) => {
+        $crate::__assume_capture!(@split $f $o [or] [$($s)* [[$($l)+] $op $r]] [] [] [] {$($m)*} $($t)+)
+    };
+    (@split $f:tt $o:tt [or] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {|| $($m:tt)*} $_:tt $($t:tt)+) => {
+        $crate::__assume_capture!(@split $f $o [or] [$($s)* [[$($l)+] $op $r]] [] [] [] {$($m)*} $($t)+)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] $op:tt $r:tt {&& $($m:tt)*} $($t:tt)+) => {
+        $crate::__assume_capture!(@whole $f $o)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] $op:tt $r:tt {|| $($m:tt)*} $($t:tt)+) => {
+        $crate::__assume_capture!(@whole $f $o)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)*] [bool] [] {$_:tt $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)*] [$($y:tt)?] [$($r:tt)*] {:: < $($m:tt)*} $x:tt $z:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)* $($y)? $($r)* $x $z] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {== $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {!= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {< $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {<= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {> $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [] [] {>= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+] [$x] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {== $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {!= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {< $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {<= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {> $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)+] [$y:tt] [$($r:tt)*] {>= $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)+ $y $($r)* $x] [bool] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt [$($l:tt)*] [] [] {$_:tt $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s [$($l)* $x] [] [] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt $k:tt $s:tt $l:tt [$y:tt] [$($r:tt)*] {$_:tt $($m:tt)*} $x:tt $($t:tt)*) => {
+        $crate::__assume_capture!(@split $f $o $k $s $l [$y] [$($r)* $x] {$($m)*} $($t)*)
+    };
+    (@split $f:tt $o:tt [] [] [$($l:tt)+] $op:tt $r:tt {}) => {
+        $crate::__assume_capture!(@single $f $o [$($l)+] $op $r)
+    };
+    (@split $f:tt $o:tt [and] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {}) => {
+        $crate::__assume_capture!(@and $f $($s)* [[$($l)+] $op $r])
+    };
+    (@split $f:tt $o:tt [or] [$($s:tt)*] [$($l:tt)+] $op:tt $r:tt {}) => {
+        $crate::__assume_capture!(@or $f [] $($s)* [[$($l)+] $op $r])
+    };
+    (@split $f:tt $o:tt $($_:tt)*) => {
+        $crate::__assume_capture!(@whole $f $o)
+    };
+
+    // A lone clause. The condition text is already in the message, so only values are added.
+    (@single $f:tt $o:tt [$($l:tt)+] [bool] []) => {
+        $crate::__assume_capture!(@whole $f $o)
+    };
+    (@single $f:tt $o:tt [$($l:tt)+] [$op:tt] [$($r:tt)+]) => {
+        match (&($($l)+), &($($r)+)) {
+            (left, right) => {
+                if !(*left $op *right) {
+                    $crate::__assume_capture!(
+                        @report $f ["\n   left: " (left) "\n  right: " (right)]
+                    )
+                }
+            }
+        }
+    };
+    (@single $f:tt $o:tt $($_:tt)*) => {
+        $crate::__assume_capture!(@whole $f $o)
+    };
+    (@whole $f:tt {$($o:tt)*}) => {
+        if !($($o)*) {
+            $crate::__assume_capture!(@report $f [])
+        }
+    };
+
+    // Conjunctions report the first clause that failed.
+    (@and $f:tt [$l:tt $op:tt $r:tt] $($rest:tt)*) => {{
+        $crate::__assume_capture!(@and_step $f $l $op $r);
+        $crate::__assume_capture!(@and $f $($rest)*)
+    }};
+    (@and $f:tt) => {
+        ()
+    };
+    (@and_step $f:tt [$($l:tt)+] [bool] []) => {
+        $crate::__assume_capture!(@and_step $f [$($l)+] [] [])
+    };
+    (@and_step $f:tt [$($l:tt)+] [$op:tt] [$($r:tt)+]) => {
+        match (&($($l)+), &($($r)+)) {
+            (left, right) => {
+                if !(*left $op *right) {
+                    $crate::__assume_capture!(
+                        @report $f [
+                            "\n  failed: " {$crate::__private::stringify!($($l)+ $op $($r)+)}
+                            "\n    left: " (left) "\n   right: " (right)
+                        ]
+                    )
+                }
+            }
+        }
+    };
+    (@and_step $f:tt [$($c:tt)+] [] []) => {
+        if !($($c)+) {
+            $crate::__assume_capture!(
+                @report $f ["\n  failed: " {$crate::__private::stringify!($($c)+)}]
+            )
+        }
+    };
+
+    // Disjunctions report every clause, so each is checked within the scope of the last
+    // in order to keep its operands alive until the report.
+    (@or $f:tt [$($p:tt)*] [$l:tt $op:tt $r:tt] $($rest:tt)*) => {
+        $crate::__assume_capture!(@or_step $f [$($p)*] [$($rest)*] $l $op $r)
+    };
+    (@or $f:tt [$($p:tt)*]) => {
+        $crate::__assume_capture!(@report $f [$($p)*])
+    };
+    (@or_step $f:tt $p:tt $rest:tt [$($l:tt)+] [bool] []) => {
+        $crate::__assume_capture!(@or_step $f $p $rest [$($l)+] [] [])
+    };
+    (@or_step $f:tt [$($p:tt)*] [$($rest:tt)*] [$($l:tt)+] [$op:tt] [$($r:tt)+]) => {
+        match (&($($l)+), &($($r)+)) {
+            (left, right) => {
+                if !(*left $op *right) {
+                    $crate::__assume_capture!(
+                        @or $f [
+                            $($p)*
+                            "\n  failed: " {$crate::__private::stringify!($($l)+ $op $($r)+)}
+                            "\n    left: " (left) "\n   right: " (right)
+                        ]
+                        $($rest)*
+                    )
+                }
+            }
+        }
+    };
+    (@or_step $f:tt [$($p:tt)*] [$($rest:tt)*] [$($c:tt)+] [] []) => {
+        if !($($c)+) {
+            $crate::__assume_capture!(
+                @or $f [$($p)* "\n  failed: " {$crate::__private::stringify!($($c)+)}]
+                $($rest)*
+            )
+        }
+    };
+
+    // Writes the report without `core::fmt`, unless the message itself is formatted, so that
+    // capturing keeps `assume!` usable in a `const fn`.
+    (@report [$cond:expr, $($message:tt)*] [$($piece:tt)*]) => {{
+        let mut message = $crate::__private::Message::new();
+        $crate::__assume_capture!(@message message $($message)*);
+        $($crate::__assume_capture!(@piece message $piece);)*
+        let message = message.as_str();
+        $crate::__assume_impl!(@fail $crate::__private::stringify!($cond), message)
+    }};
+    (@message $m:ident $text:expr $(,)?) => {
+        $m.push_str($text)
+    };
+    (@message $m:ident $fmt:expr, $($args:tt)+) => {
+        $crate::__private::write_message(&mut $m, $crate::__private::format_args!($fmt, $($args)+))
+    };
+    (@piece $m:ident ($value:expr)) => {
+        (&$crate::__private::Captured($value)).__assume_write(&mut $m)
+    };
+    (@piece $m:ident {$text:expr}) => {
+        $m.push_str($text)
+    };
+    (@piece $m:ident $text:literal) => {
+        $m.push_str($text)
+    };
+}
+
+/// The message of a failed assumption with captured operands.
+///
+/// Written without `core::fmt` for operands of integer, `bool` and `char` comparisons, which
+/// are the ones a `const fn` can make. Long messages are cut short at `CAPACITY` bytes.
+#[doc(hidden)]
+pub struct Message {
+    bytes: [u8; Message::CAPACITY],
+    len: usize,
+    full: bool,
+}
+
+impl Message {
+    const CAPACITY: usize = 512;
+
+    #[allow(clippy::new_without_default)]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Message {
+            bytes: [0; Message::CAPACITY],
+            len: 0,
+            full: false,
+        }
+    }
+
+    pub const fn push_str(&mut self, text: &str) {
+        let text = text.as_bytes();
+        if self.full {
+            return;
+        }
+        let mut len = text.len();
+        if len > Message::CAPACITY - self.len {
+            len = Message::CAPACITY - self.len;
+            // Cut at a char boundary, to stay valid UTF-8.
+            while len > 0 && text[len] & 0xc0 == 0x80 {
+                len -= 1;
+            }
+            self.full = true;
+        }
+        let mut i = 0;
+        while i < len {
+            self.bytes[self.len + i] = text[i];
+            i += 1;
+        }
+        self.len += len;
+    }
+
+    const fn push_u128(&mut self, mut value: u128) {
+        let mut digits = [0; 39];
+        let mut start = digits.len();
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        // Safe, as the digits are ASCII.
+        self.push_str(unsafe { from_utf8_unchecked(digits.split_at(start).1) });
+    }
+
+    const fn push_i128(&mut self, value: i128) {
+        if value < 0 {
+            self.push_str("-");
+        }
+        self.push_u128(value.unsigned_abs());
+    }
+
+    const fn push_char(&mut self, value: char) {
+        let mut utf8 = [0; 4];
+        self.push_str(value.encode_utf8(&mut utf8));
+    }
+
+    /// Escapes as `Debug` does, except for the rare non-ASCII chars it also escapes.
+    const fn push_debug_char(&mut self, value: char) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        self.push_str("'");
+        match value {
+            '\0' => self.push_str("\\0"),
+            '\t' => self.push_str("\\t"),
+            '\r' => self.push_str("\\r"),
+            '\n' => self.push_str("\\n"),
+            '\'' => self.push_str("\\'"),
+            '\\' => self.push_str("\\\\"),
+            c if c.is_ascii_control() => {
+                self.push_str("\\u{");
+                if c as u8 >= 0x10 {
+                    self.push_char(HEX[c as usize >> 4] as char);
+                }
+                self.push_char(HEX[c as usize & 0xf] as char);
+                self.push_str("}");
+            }
+            c => self.push_char(c),
+        }
+        self.push_str("'");
+    }
+
+    pub const fn as_str(&self) -> &str {
+        // Safe, as only whole chars are pushed.
+        unsafe { from_utf8_unchecked(self.bytes.split_at(self.len).0) }
+    }
+}
+
+impl fmt::Write for Message {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.push_str(text);
+        Ok(())
+    }
+}
+
+/// Writes a formatted message, which is not possible in a `const fn` anyway.
+#[doc(hidden)]
+pub fn write_message(message: &mut Message, args: fmt::Arguments) {
+    let _ = fmt::Write::write_fmt(message, args);
+}
+
+/// An operand of a failed assumption, formatted with `Debug` if it implements it.
+#[doc(hidden)]
+pub struct Captured<'a, T: ?Sized>(pub &'a T);
+
+// Taking precedence over the traits below, this writes as `Debug` would for the operands a
+// `const fn` can compare. A single impl, so that it applies to integer literals of no type yet.
+impl<'a, T: Primitive> Captured<'a, T> {
+    #[inline(always)]
+    pub const fn __assume_write(&self, message: &mut Message) {
+        let value = self.0 as *const T;
+        // Safe, as the kind and size are those of `T`.
+        unsafe {
+            match T::KIND {
+                Kind::Unsigned => message.push_u128(match size_of::<T>() {
+                    1 => *(value as *const u8) as u128,
+                    2 => *(value as *const u16) as u128,
+                    4 => *(value as *const u32) as u128,
+                    8 => *(value as *const u64) as u128,
+                    _ => *(value as *const u128),
+                }),
+                Kind::Signed => message.push_i128(match size_of::<T>() {
+                    1 => *(value as *const i8) as i128,
+                    2 => *(value as *const i16) as i128,
+                    4 => *(value as *const i32) as i128,
+                    8 => *(value as *const i64) as i128,
+                    _ => *(value as *const i128),
+                }),
+                Kind::Bool => message.push_str(if *(value as *const bool) {
+                    "true"
+                } else {
+                    "false"
+                }),
+                Kind::Char => message.push_debug_char(*(value as *const char)),
+            }
+        }
+    }
+}
+
+/// A type whose operands are written without `core::fmt`.
+///
+/// # Safety
+/// `KIND` must be that of the type.
+#[doc(hidden)]
+pub unsafe trait Primitive {
+    const KIND: Kind;
+}
+
+#[doc(hidden)]
+pub enum Kind {
+    Unsigned,
+    Signed,
+    Bool,
+    Char,
+}
+
+macro_rules! primitives {
+    ($kind:ident: $($ty:ty)*) => {$(
+        unsafe impl Primitive for $ty {
+            const KIND: Kind = Kind::$kind;
+        }
+    )*};
+}
+
+primitives!(Unsigned: u8 u16 u32 u64 u128 usize);
+primitives!(Signed: i8 i16 i32 i64 i128 isize);
+primitives!(Bool: bool);
+primitives!(Char: char);
+
+/// Selected by method resolution for operands that implement `Debug`.
+#[doc(hidden)]
+pub trait CapturedDebug {
+    fn __assume_value(&self) -> &dyn fmt::Debug;
+
+    fn __assume_write(&self, message: &mut Message) {
+        write_message(message, format_args!("{:?}", self.__assume_value()));
+    }
+}
+
+impl<'a, T: fmt::Debug + ?Sized> CapturedDebug for Captured<'a, T> {
+    fn __assume_value(&self) -> &dyn fmt::Debug {
+        &self.0
+    }
+}
+
+/// Selected by method resolution, after auto-ref, for all other operands.
+#[doc(hidden)]
+pub trait CapturedOpaque {
+    fn __assume_value(&self) -> &dyn fmt::Debug;
+
+    fn __assume_write(&self, message: &mut Message) {
+        message.push_str("<not Debug>");
+    }
+}
+
+impl<'a, 'b, T: ?Sized> CapturedOpaque for &'b Captured<'a, T> {
+    fn __assume_value(&self) -> &dyn fmt::Debug {
+        &Opaque
+    }
+}
+
+struct Opaque;
+
+impl fmt::Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<not Debug>")
+    }
+}
@@ -0,0 +1,108 @@
+//! Conversion assumptions.
+
+/// Assumes that the given value fits in the given type, evaluating to the converted value.
+///
+/// This is `x as T` without the truncation, for any conversion provided by [`TryFrom`] (such
+/// as between integer types). The value is evaluated once. In checked configurations a value
+/// that does not fit panics with the value. Otherwise, the optimizer may rely on the value
+/// being in the range of the target type, both before and after the conversion.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// [`TryFrom`]: core::convert::TryFrom
+///
+/// ```
+/// # fn get_index() -> usize { 0 }
+/// # fn main() {
+/// use assume::assume_cast;
+///
+/// let table = [0u32; 256];
+///
+/// // Some index that, per invariants, always refers to a byte.
+/// let i: usize = get_index();
+///
+/// let byte = assume_cast!(unsafe: i => u8);
+/// let entry = table[byte as usize];  // Bounds check optimized out per assumption.
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_cast {
+    (unsafe: $value:expr => $ty:ty $(,)?) => {{
+        $crate::__assume_cast!($value, $ty, "", "")
+    }};
+    (unsafe: $value:expr => $ty:ty, $msg:expr $(,)?) => {{
+        $crate::__assume_cast!($value, $ty, ": ", $msg)
+    }};
+    (unsafe: $value:expr => $ty:ty, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_cast!($value, $ty, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be of the form 'value => Type'");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_cast {
+    ($value:expr, $ty:ty, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $value } {
+            value => match <$ty as $crate::__private::TryFrom<_>>::try_from(
+                $crate::__private::Clone::clone(&value),
+            ) {
+                $crate::__private::Ok(converted) => converted,
+                $crate::__private::Err(_) => {
+                    #[allow(unused_imports)]
+                    use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                    $crate::__assume_impl!(
+                        @fail $crate::__private::concat!(
+                            $crate::__private::stringify!($value),
+                            " fits in ",
+                            $crate::__private::stringify!($ty)
+                        ),
+                        "assumption failed: {} fits in {}{}{}\n  value: {:?}",
+                        $crate::__private::stringify!($value),
+                        $crate::__private::stringify!($ty),
+                        $separator,
+                        $msg,
+                        (&$crate::__private::Captured(&value)).__assume_value(),
+                    )
+                }
+            },
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_converted_value() {
+        let value = 200u32;
+        let byte: u8 = assume_cast!(unsafe: value => u8);
+        assert_eq!(byte, 200);
+        assert_eq!(assume_cast!(unsafe: -1i64 => i8, "oh no"), -1);
+        assert_eq!(
+            assume_cast!(unsafe: byte => u64, "oh no, a {}", "problem"),
+            200
+        );
+    }
+
+    #[test]
+    fn evaluates_value_once() {
+        let mut calls = 0u32;
+        assume_cast!(unsafe: { calls += 1; calls } => u8);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value + 1 fits in u8: oh no\n  value: 256")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_value() {
+        let value = 255u32;
+        assume_cast!(unsafe: value + 1 => u8, "oh no");
+    }
+}
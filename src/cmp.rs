@@ -0,0 +1,133 @@
+//! Equality assumptions.
+
+/// Assumes that two expressions are equal to each other (using [`PartialEq`]).
+///
+/// This is the `assume!` counterpart of [`assert_eq!`]. Each operand is evaluated once, and in
+/// checked configurations a failure reports the [`Debug`] representation of both. Otherwise,
+/// the comparison is given to the optimizer as with `assume!`.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_eq;
+///
+/// let v = vec![1, 2, 3];
+/// let w = vec![4, 5, 6];
+///
+/// assume_eq!(unsafe: v.len(), w.len(), "vectors must be zipped");
+/// # }
+/// ```
+///
+/// [`Debug`]: core::fmt::Debug
+#[macro_export]
+macro_rules! assume_eq {
+    (unsafe: $left:expr, $right:expr $(, $($message:tt)*)?) => {{
+        $crate::__assume_cmp!($left, ==, $right $(, $($message)*)?)
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be two comma-separated expressions");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that two expressions are not equal to each other (using [`PartialEq`]).
+///
+/// This is the `assume!` counterpart of [`assert_ne!`]. See [`assume_eq!`] for more.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_ne;
+///
+/// let divisor = 3;
+///
+/// assume_ne!(unsafe: divisor, 0);
+/// let quotient = 12 / divisor;  // Division by zero check optimized out per assumption.
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_ne {
+    (unsafe: $left:expr, $right:expr $(, $($message:tt)*)?) => {{
+        $crate::__assume_cmp!($left, !=, $right $(, $($message)*)?)
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be two comma-separated expressions");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_cmp {
+    ($left:expr, $op:tt, $right:expr $(,)?) => {
+        $crate::__assume_cmp!(@report $left, $op, $right, "", "")
+    };
+    ($left:expr, $op:tt, $right:expr, $msg:expr $(,)?) => {
+        $crate::__assume_cmp!(@report $left, $op, $right, ": ", $msg)
+    };
+    ($left:expr, $op:tt, $right:expr, $fmt:expr, $($args:tt)*) => {
+        $crate::__assume_cmp!(
+            @report $left,
+            $op,
+            $right,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    (@report $left:expr, $op:tt, $right:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { (&$left, &$right) } {
+            (left, right) => {
+                $crate::__assume_impl!(
+                    @check *left $op *right,
+                    $crate::__private::stringify!($left $op $right),
+                    "assumption failed: {}{}{}\n   left: {:?}\n  right: {:?}",
+                    $crate::__private::stringify!($left $op $right),
+                    $separator,
+                    $msg,
+                    left,
+                    right,
+                );
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn operands_are_evaluated_once() {
+        let mut calls = 0;
+        assume_eq!(unsafe: { calls += 1; calls }, 1);
+        assume_ne!(unsafe: { calls += 1; calls }, 1);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn operands_are_not_moved() {
+        let left = ::std::string::String::from("a");
+        assume_eq!(unsafe: left, "a");
+        assume_ne!(unsafe: left, "b", "strings must differ");
+        assert_eq!(left, "a");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: 1 + 1 == 3\n   left: 2\n  right: 3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn eq_reports_operands() {
+        assume_eq!(unsafe: 1 + 1, 3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 2 != 2: oh no, a problem\n   left: 2\n  right: 2"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn ne_reports_operands_with_format() {
+        assume_ne!(unsafe: 2, 2, "oh no, a {}", "problem");
+    }
+}
@@ -0,0 +1,170 @@
+//! Support for the function contract attributes.
+
+use core::convert::Infallible;
+use core::ops::ControlFlow;
+use core::task::Poll;
+
+/// Assumes a postcondition, written as a closure, of the given return value.
+///
+/// The closure arrives as written so that `assume!` quotes the condition with its spacing.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_ensures {
+    ($ret:ident, $msg:expr, | $($tokens:tt)+) => {
+        $crate::__assume_ensures!(@pattern $ret, $msg, [] $($tokens)+)
+    };
+    (@pattern $ret:ident, $msg:expr, [$($pattern:tt)+] | $($condition:tt)+) => {{
+        let $($pattern)+ = &$ret;
+        $crate::assume!(unsafe: $($condition)+, $msg);
+    }};
+    (@pattern $ret:ident, $msg:expr, [$($pattern:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::__assume_ensures!(@pattern $ret, $msg, [$($pattern)* $t] $($rest)*)
+    };
+}
+
+/// Stands in for the unstable `Try` trait where `#[ensures]` rewrites `?`.
+///
+/// A `?` in the body of a function with a postcondition breaks out to the postcondition with
+/// the converted error, rather than returning it. The types of the standard library that `?`
+/// applies to on stable are supported: `Result`, `Option`, `ControlFlow`, and `Poll` of a
+/// `Result` or of an `Option` of one.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`?` on `{Self}` is not supported in functions with postconditions or invariants",
+    label = "not supported by `#[ensures]` and `#[maintains_invariant]`",
+    note = "only `Result`, `Option`, `ControlFlow` and `Poll` of a `Result` or of an `Option` \
+            of one are supported, as `Try` is unstable"
+)]
+pub trait __Try {
+    type Output;
+    type Residual;
+
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output>;
+}
+
+/// Stands in for the unstable `FromResidual` trait, as with [`__Try`].
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`?` cannot convert `{R}` into `{Self}` in a function with a postcondition or \
+               invariant",
+    note = "the conversions of the standard library between `Result`, `Option`, \
+            `ControlFlow` and `Poll` are supported"
+)]
+pub trait __FromResidual<R> {
+    fn from_residual(residual: R) -> Self;
+}
+
+impl<T, E> __Try for Result<T, E> {
+    type Output = T;
+    type Residual = Result<Infallible, E>;
+
+    #[inline(always)]
+    fn branch(self) -> ControlFlow<Self::Residual, T> {
+        match self {
+            Ok(value) => ControlFlow::Continue(value),
+            Err(error) => ControlFlow::Break(Err(error)),
+        }
+    }
+}
+
+impl<T, E, F: From<E>> __FromResidual<Result<Infallible, E>> for Result<T, F> {
+    #[inline(always)]
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
+        match residual {
+            Ok(never) => match never {},
+            Err(error) => Err(From::from(error)),
+        }
+    }
+}
+
+impl<T> __Try for Option<T> {
+    type Output = T;
+    type Residual = Option<Infallible>;
+
+    #[inline(always)]
+    fn branch(self) -> ControlFlow<Self::Residual, T> {
+        match self {
+            Some(value) => ControlFlow::Continue(value),
+            None => ControlFlow::Break(None),
+        }
+    }
+}
+
+impl<T> __FromResidual<Option<Infallible>> for Option<T> {
+    #[inline(always)]
+    fn from_residual(_: Option<Infallible>) -> Self {
+        None
+    }
+}
+
+impl<B, C> __Try for ControlFlow<B, C> {
+    type Output = C;
+    type Residual = ControlFlow<B, Infallible>;
+
+    #[inline(always)]
+    fn branch(self) -> ControlFlow<Self::Residual, C> {
+        match self {
+            ControlFlow::Continue(value) => ControlFlow::Continue(value),
+            ControlFlow::Break(value) => ControlFlow::Break(ControlFlow::Break(value)),
+        }
+    }
+}
+
+impl<B, C> __FromResidual<ControlFlow<B, Infallible>> for ControlFlow<B, C> {
+    #[inline(always)]
+    fn from_residual(residual: ControlFlow<B, Infallible>) -> Self {
+        match residual {
+            ControlFlow::Break(value) => ControlFlow::Break(value),
+            ControlFlow::Continue(never) => match never {},
+        }
+    }
+}
+
+impl<T, E> __Try for Poll<Result<T, E>> {
+    type Output = Poll<T>;
+    type Residual = Result<Infallible, E>;
+
+    #[inline(always)]
+    fn branch(self) -> ControlFlow<Self::Residual, Poll<T>> {
+        match self {
+            Poll::Ready(Ok(value)) => ControlFlow::Continue(Poll::Ready(value)),
+            Poll::Ready(Err(error)) => ControlFlow::Break(Err(error)),
+            Poll::Pending => ControlFlow::Continue(Poll::Pending),
+        }
+    }
+}
+
+impl<T, E, F: From<E>> __FromResidual<Result<Infallible, E>> for Poll<Result<T, F>> {
+    #[inline(always)]
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
+        match residual {
+            Ok(never) => match never {},
+            Err(error) => Poll::Ready(Err(From::from(error))),
+        }
+    }
+}
+
+impl<T, E> __Try for Poll<Option<Result<T, E>>> {
+    type Output = Poll<Option<T>>;
+    type Residual = Result<Infallible, E>;
+
+    #[inline(always)]
+    fn branch(self) -> ControlFlow<Self::Residual, Poll<Option<T>>> {
+        match self {
+            Poll::Ready(Some(Ok(value))) => ControlFlow::Continue(Poll::Ready(Some(value))),
+            Poll::Ready(Some(Err(error))) => ControlFlow::Break(Err(error)),
+            Poll::Ready(None) => ControlFlow::Continue(Poll::Ready(None)),
+            Poll::Pending => ControlFlow::Continue(Poll::Pending),
+        }
+    }
+}
+
+impl<T, E, F: From<E>> __FromResidual<Result<Infallible, E>> for Poll<Option<Result<T, F>>> {
+    #[inline(always)]
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
+        match residual {
+            Ok(never) => match never {},
+            Err(error) => Poll::Ready(Some(Err(From::from(error)))),
+        }
+    }
+}
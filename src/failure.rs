@@ -0,0 +1,187 @@
+//! Reporting of assumption violations in checked builds.
+
+use core::fmt;
+#[cfg(feature = "violation-handler")]
+use core::mem;
+#[cfg(any(feature = "std", feature = "violation-handler"))]
+use core::panic::Location;
+#[cfg(feature = "violation-handler")]
+use core::ptr;
+#[cfg(feature = "violation-handler")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(feature = "std")]
+use std::string::{String, ToString};
+
+#[cfg(feature = "violation-handler")]
+/// Details of an assumption that failed to hold in a checked build.
+///
+/// Passed to the handler installed with [`set_violation_handler`].
+#[derive(Debug)]
+pub struct AssumptionFailure<'a> {
+    location: &'static Location<'static>,
+    condition: &'static str,
+    message: fmt::Arguments<'a>,
+}
+
+#[cfg(feature = "violation-handler")]
+impl<'a> AssumptionFailure<'a> {
+    /// The location of the failed assumption, which is that of the caller for assumptions
+    /// made in `#[track_caller]` functions, as in its panic.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// The file containing the failed assumption.
+    pub fn file(&self) -> &'static str {
+        self.location.file()
+    }
+
+    /// The line of the failed assumption.
+    pub fn line(&self) -> u32 {
+        self.location.line()
+    }
+
+    /// The column of the failed assumption.
+    pub fn column(&self) -> u32 {
+        self.location.column()
+    }
+
+    /// The stringified condition, or `unreachable` for `@unreachable`.
+    pub fn condition(&self) -> &'static str {
+        self.condition
+    }
+
+    /// The formatted failure message.
+    pub fn message(&self) -> fmt::Arguments<'a> {
+        self.message
+    }
+}
+
+#[cfg(feature = "violation-handler")]
+impl<'a> fmt::Display for AssumptionFailure<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+#[cfg(feature = "violation-handler")]
+/// Installed violation handler, stored as an erased `fn(&AssumptionFailure)`.
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+#[cfg(feature = "violation-handler")]
+/// Installs a handler to be called when an assumption fails in a checked build.
+///
+/// The handler runs just before the panic of the failed assumption, so ahead of the panic hook
+/// and regardless of the panic strategy. It may log the failure, abort, or enter a debugger; if
+/// it returns, the panic proceeds as normal, and it must not panic itself. Replaces any
+/// previously installed handler.
+///
+/// With this feature on, `assume!` is not usable in a `const fn`.
+pub fn set_violation_handler(handler: fn(&AssumptionFailure<'_>)) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+#[cfg(feature = "violation-handler")]
+#[cold]
+#[inline(never)]
+fn violated(failure: &AssumptionFailure<'_>) {
+    let handler = HANDLER.load(Ordering::Acquire);
+    if !handler.is_null() {
+        // SAFETY: Only ever stored from a `fn(&AssumptionFailure)` in `set_violation_handler`.
+        let handler: fn(&AssumptionFailure<'_>) = unsafe { mem::transmute(handler) };
+        handler(failure);
+    }
+}
+
+/// An assumption that failed to hold in a checked build, as the payload of its panic.
+///
+/// Used with the `std` feature, which panics with [`std::panic::panic_any`]. A `catch_unwind`
+/// caller can then tell an assumption violation apart from any other panic by downcasting the
+/// payload, and get at its parts.
+///
+/// The payload is not a string, so the default panic hook prints `Box<dyn Any>` in place of the
+/// message, and `#[should_panic(expected = "...")]` cannot match it. A panic hook can print the
+/// message with the `Display` implementation, and a test can match it in the same way, after
+/// `catch_unwind`.
+///
+/// ```
+/// use assume::{assume, AssumptionViolated};
+///
+/// # #[cfg(debug_assertions)] {
+/// let payload = std::panic::catch_unwind(|| {
+///     assume!(unsafe: 1 > 2, "oh no");
+/// })
+/// .unwrap_err();
+///
+/// let violated = payload.downcast_ref::<AssumptionViolated>().unwrap();
+/// assert_eq!(violated.condition(), "1 > 2");
+/// assert!(violated.to_string().starts_with("assumption failed: 1 > 2: oh no"));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct AssumptionViolated {
+    location: &'static Location<'static>,
+    condition: &'static str,
+    message: String,
+}
+
+#[cfg(feature = "std")]
+impl AssumptionViolated {
+    /// The location of the failed assumption.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// The stringified condition, or `unreachable` for `@unreachable`.
+    pub fn condition(&self) -> &'static str {
+        self.condition
+    }
+
+    /// The formatted failure message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for AssumptionViolated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AssumptionViolated {}
+
+/// Reports a failed assumption, then panics with it: as an `AssumptionViolated` with `std`, or
+/// with its message otherwise.
+///
+/// Not `const`, which is what keeps `assume!` out of `const` contexts with the features that
+/// report failures: the handler has to run before the panic starts, so that it runs with
+/// `panic = "abort"` too, and ahead of the panic hook.
+#[cold]
+#[inline(never)]
+#[track_caller]
+#[doc(hidden)]
+pub fn fail(condition: &'static str, message: fmt::Arguments<'_>) -> ! {
+    #[cfg(feature = "violation-handler")]
+    violated(&AssumptionFailure {
+        location: Location::caller(),
+        condition,
+        message,
+    });
+
+    #[cfg(feature = "std")]
+    std::panic::panic_any(AssumptionViolated {
+        location: Location::caller(),
+        condition,
+        message: message.to_string(),
+    });
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = condition;
+        panic!("{}", message)
+    }
+}
@@ -0,0 +1,457 @@
+//! Floating point assumptions.
+
+/// Assumes that the given float is finite (neither infinite nor NaN), evaluating to it.
+///
+/// The value is evaluated once. In checked configurations any other value panics with the
+/// value. Otherwise, the optimizer may rely on the value being finite.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_weight() -> f32 { 0.5 }
+/// # fn main() {
+/// use assume::assume_finite;
+///
+/// // Some weight that, per invariants, is always finite.
+/// let weight = assume_finite!(unsafe: get_weight());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_finite {
+    (unsafe: $value:expr $(,)?) => {{
+        $crate::__assume_float!(is_finite, " is finite", $value, "", "")
+    }};
+    (unsafe: $value:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_float!(is_finite, " is finite", $value, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_float!(
+            is_finite,
+            " is finite",
+            $value,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a float");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given float is not NaN, evaluating to it.
+///
+/// The value is evaluated once. In checked configurations a NaN panics. Otherwise, the
+/// optimizer may rely on the value not being NaN. Note that optimizers make limited use of
+/// such facts about floats: at the time of writing, LLVM folds tests for NaN of the value and
+/// of arithmetic on it, but still lowers comparisons, `partial_cmp`, `min`, `max` and casts to
+/// integers with their NaN handling. Use [`assume_float_cmp!`], [`assume_float_min!`] and
+/// [`assume_float_max!`] for comparisons without it. See `examples/float.rs`.
+///
+/// [`assume_float_cmp!`]: crate::assume_float_cmp
+/// [`assume_float_min!`]: crate::assume_float_min
+/// [`assume_float_max!`]: crate::assume_float_max
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_sample() -> f64 { 0.5 }
+/// # fn main() {
+/// use assume::assume_not_nan;
+///
+/// // Some sample that, per invariants, is never NaN.
+/// let sample = assume_not_nan!(unsafe: get_sample(), "samples are validated on input");
+///
+/// let louder = sample * 2.0;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_not_nan {
+    (unsafe: $value:expr $(,)?) => {{
+        $crate::__assume_float!(is_not_nan, " is not NaN", $value, "", "")
+    }};
+    (unsafe: $value:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_float!(is_not_nan, " is not NaN", $value, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_float!(
+            is_not_nan,
+            " is not NaN",
+            $value,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a float");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given float is in the given range, evaluating to it.
+///
+/// Written as `value in range`, for any range type. As a range contains no NaN, this also
+/// assumes that the value is not NaN. The value is evaluated once. In checked configurations
+/// a value outside of the range panics with the value. Otherwise, the optimizer may rely on
+/// the value being in the range.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_alpha() -> f32 { 0.5 }
+/// # fn main() {
+/// use assume::assume_float_range;
+///
+/// // Some alpha that, per invariants, is always normalized.
+/// let alpha = assume_float_range!(unsafe: get_alpha() in 0.0..=1.0);
+///
+/// let byte = (alpha * 255.0) as u8;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_float_range {
+    (unsafe: $($tokens:tt)+) => {{
+        $crate::__assume_float_range!(@munch [] $($tokens)+)
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Compares two floats that are assumed not to be NaN, evaluating to their [`Ordering`].
+///
+/// This is the counterpart of `left.partial_cmp(&right).unwrap()`. Each operand is evaluated
+/// once, and in checked configurations a NaN panics with both values. Otherwise, the ordering
+/// is found with `<` and `>` alone, so the generated code has no NaN case to handle, let alone
+/// a panic for it.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_float_cmp;
+///
+/// // Scores that, per invariants, are never NaN.
+/// let mut scores = vec![0.5f64, -1.0, 2.0];
+/// scores.sort_by(|a, b| assume_float_cmp!(unsafe: *a, *b));
+/// # assert_eq!(scores, [-1.0, 0.5, 2.0]);
+/// # }
+/// ```
+///
+/// [`Ordering`]: core::cmp::Ordering
+#[macro_export]
+macro_rules! assume_float_cmp {
+    (unsafe: $left:expr, $right:expr $(, $($message:tt)*)?) => {{
+        $crate::__assume_float_pair!(cmp, $left, $right $(, $($message)*)?)
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be two comma-separated expressions");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Evaluates to the lesser of two floats that are assumed not to be NaN.
+///
+/// This is the counterpart of `left.min(right)`, without its handling of a NaN operand: in
+/// checked configurations a NaN panics with both values, and otherwise the minimum is selected
+/// with `<` alone (e.g. a single `minsd` on x86-64). Of two zeros, `right` is chosen. See
+/// [`assume_float_cmp!`] for more.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_float_min;
+///
+/// // Distances that, per invariants, are never NaN.
+/// let distances = [3.0f32, 1.5, 2.0];
+/// let nearest = distances
+///     .iter()
+///     .fold(f32::INFINITY, |a, &b| assume_float_min!(unsafe: a, b));
+/// # assert_eq!(nearest, 1.5);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_float_min {
+    (unsafe: $left:expr, $right:expr $(, $($message:tt)*)?) => {{
+        $crate::__assume_float_pair!(min, $left, $right $(, $($message)*)?)
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be two comma-separated expressions");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Evaluates to the greater of two floats that are assumed not to be NaN.
+///
+/// This is the counterpart of `left.max(right)`. See [`assume_float_min!`] for more.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_float_max;
+///
+/// // Some gain that, per invariants, is never NaN.
+/// let gain = 0.75f64;
+/// let boosted = assume_float_max!(unsafe: gain, 1.0, "gains are validated on input");
+/// # assert_eq!(boosted, 1.0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_float_max {
+    (unsafe: $left:expr, $right:expr $(, $($message:tt)*)?) => {{
+        $crate::__assume_float_pair!(max, $left, $right $(, $($message)*)?)
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be two comma-separated expressions");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_float {
+    ($predicate:ident, $description:expr, $value:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $value } {
+            value => {
+                $crate::__assume_impl!(
+                    @check $crate::__assume_float!(@$predicate value),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($value),
+                        $description
+                    ),
+                    "assumption failed: {}{}{}{}\n  value: {:?}",
+                    $crate::__private::stringify!($value),
+                    $description,
+                    $separator,
+                    $msg,
+                    value,
+                );
+                value
+            }
+        }
+    };
+    (@is_finite $value:ident) => {
+        $value.is_finite()
+    };
+    (@is_not_nan $value:ident) => {
+        !$value.is_nan()
+    };
+}
+
+/// Checks that neither of two floats is NaN, then applies a comparison that ignores NaN.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_float_pair {
+    ($op:ident, $left:expr, $right:expr $(,)?) => {
+        $crate::__assume_float_pair!(@report $op, $left, $right, "", "")
+    };
+    ($op:ident, $left:expr, $right:expr, $msg:expr $(,)?) => {
+        $crate::__assume_float_pair!(@report $op, $left, $right, ": ", $msg)
+    };
+    ($op:ident, $left:expr, $right:expr, $fmt:expr, $($args:tt)*) => {
+        $crate::__assume_float_pair!(
+            @report $op,
+            $left,
+            $right,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    (@report $op:ident, $left:expr, $right:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { [$left, $right] } {
+            [left, right] => {
+                $crate::__assume_impl!(
+                    @check !left.is_nan() && !right.is_nan(),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($left),
+                        " and ",
+                        $crate::__private::stringify!($right),
+                        " are not NaN"
+                    ),
+                    "assumption failed: {} and {} are not NaN{}{}\n   left: {:?}\n  right: {:?}",
+                    $crate::__private::stringify!($left),
+                    $crate::__private::stringify!($right),
+                    $separator,
+                    $msg,
+                    left,
+                    right,
+                );
+                $crate::__assume_float_pair!(@$op left, right)
+            }
+        }
+    };
+    (@cmp $left:ident, $right:ident) => {
+        if $left < $right {
+            $crate::__private::Ordering::Less
+        } else if $left > $right {
+            $crate::__private::Ordering::Greater
+        } else {
+            $crate::__private::Ordering::Equal
+        }
+    };
+    (@min $left:ident, $right:ident) => {
+        if $left < $right {
+            $left
+        } else {
+            $right
+        }
+    };
+    (@max $left:ident, $right:ident) => {
+        if $left > $right {
+            $left
+        } else {
+            $right
+        }
+    };
+}
+
+/// Splits the value from the range at the first top-level `in`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_float_range {
+    (@munch [$($value:tt)+] in $range:expr $(,)?) => {
+        $crate::__assume_float_range!([$($value)+], $range, "", "")
+    };
+    (@munch [$($value:tt)+] in $range:expr, $msg:expr $(,)?) => {
+        $crate::__assume_float_range!([$($value)+], $range, ": ", $msg)
+    };
+    (@munch [$($value:tt)+] in $range:expr, $fmt:expr, $($args:tt)*) => {
+        $crate::__assume_float_range!(
+            [$($value)+],
+            $range,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    (@munch [$($value:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::__assume_float_range!(@munch [$($value)* $t] $($rest)*)
+    };
+    (@munch $($_:tt)*) => {
+        $crate::__private::compile_error!("assumption must be of the form 'value in range'")
+    };
+    ([$($value:tt)+], $range:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($($value)+, $range) } {
+            (value, range) => {
+                $crate::__assume_impl!(
+                    @check $crate::__private::RangeBounds::contains(&range, &value),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($($value)+),
+                        " in ",
+                        $crate::__private::stringify!($range)
+                    ),
+                    "assumption failed: {} in {}{}{}\n  value: {:?}",
+                    $crate::__private::stringify!($($value)+),
+                    $crate::__private::stringify!($range),
+                    $separator,
+                    $msg,
+                    value,
+                );
+                value
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_value() {
+        let value = 0.5f32;
+        assert_eq!(assume_finite!(unsafe: value), 0.5);
+        assert_eq!(assume_finite!(unsafe: -1e300f64, "oh no"), -1e300);
+        assert_eq!(assume_not_nan!(unsafe: value * 2.0), 1.0);
+        assert_eq!(
+            assume_not_nan!(unsafe: f64::INFINITY, "oh no, a {}", "problem"),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    fn evaluates_to_value_in_range() {
+        let value = 0.5f64;
+        assert_eq!(assume_float_range!(unsafe: value in 0.0..=1.0), 0.5);
+        assert_eq!(
+            assume_float_range!(unsafe: value * 2.0 in ..=1.0, "oh no"),
+            1.0
+        );
+        assert_eq!(
+            assume_float_range!(unsafe: -value in -1.0..0.0, "oh no, a {}", "problem"),
+            -0.5
+        );
+    }
+
+    #[test]
+    fn compares_without_nan() {
+        use std::cmp::Ordering;
+
+        let (low, high) = (-0.5f64, 2.0f64);
+        assert_eq!(assume_float_cmp!(unsafe: low, high), Ordering::Less);
+        assert_eq!(
+            assume_float_cmp!(unsafe: high, low, "oh no"),
+            Ordering::Greater
+        );
+        assert_eq!(assume_float_cmp!(unsafe: 0.0f32, -0.0), Ordering::Equal);
+        assert_eq!(assume_float_min!(unsafe: low, high), low);
+        assert_eq!(
+            assume_float_min!(unsafe: high, low, "oh no, a {}", "problem"),
+            low
+        );
+        assert_eq!(assume_float_max!(unsafe: low, high), high);
+        assert_eq!(assume_float_max!(unsafe: f32::INFINITY, 1.0), f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: value and 1.0 are not NaN: oh no\n   left: NaN\n  right: 1.0"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_nan_compared() {
+        let value = f64::NAN;
+        assume_float_cmp!(unsafe: value, 1.0, "oh no");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 1.0 and value are not NaN\n   left: 1.0\n  right: NaN"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_nan_in_max() {
+        let value = f32::NAN;
+        assume_float_max!(unsafe: 1.0, value);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value / 0.0 is finite: oh no\n  value: inf")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_infinite_value() {
+        let value = 1.0f32;
+        assume_finite!(unsafe: value / 0.0, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value is not NaN\n  value: NaN")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_nan() {
+        let value = f64::NAN;
+        assume_not_nan!(unsafe: value);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value in 0.0..1.0: oh no\n  value: NaN")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_value_out_of_range() {
+        let value = f32::NAN;
+        assume_float_range!(unsafe: value in 0.0..1.0, "oh no");
+    }
+}
@@ -0,0 +1,200 @@
+//! Indexing assumptions.
+
+/// Assumes that the given index is in bounds of the given slice, evaluating to the element.
+///
+/// The index can be anything a slice can be indexed with, such as a `usize` or a range (in
+/// which case this evaluates to a subslice), as long as it is also `Clone`. Each operand is
+/// evaluated once.
+///
+/// Because the assumption is checked against the same slice that is indexed, the two cannot
+/// get out of sync. In checked configurations an out of bounds index panics with the index
+/// and length. Otherwise, this is `get_unchecked`.
+///
+/// Accepts the same optional message as `assume!`. See [`assume_in_bounds_mut!`] for mutable
+/// access.
+///
+/// [`assume_in_bounds_mut!`]: crate::assume_in_bounds_mut
+///
+/// ```
+/// # fn get_index() -> usize { 0 }
+/// # fn main() {
+/// use assume::assume_in_bounds;
+///
+/// let v = vec![1, 2, 3];
+///
+/// // Some computed index that, per invariants, is always in bounds.
+/// let i = get_index();
+///
+/// let element: &u32 = assume_in_bounds!(unsafe: v, i);
+/// let rest: &[u32] = assume_in_bounds!(unsafe: v, i.., "index {} beyond vec", i);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_in_bounds {
+    (unsafe: $slice:expr, $index:expr $(,)?) => {{
+        $crate::__assume_in_bounds!(get, get_unchecked, [&$slice[..]], $slice, $index, "", "")
+    }};
+    (unsafe: $slice:expr, $index:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_in_bounds!(get, get_unchecked, [&$slice[..]], $slice, $index, ": ", $msg)
+    }};
+    (unsafe: $slice:expr, $index:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_in_bounds!(
+            get,
+            get_unchecked,
+            [&$slice[..]],
+            $slice,
+            $index,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and an index");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given index is in bounds of the given slice, evaluating to the element
+/// mutably.
+///
+/// See [`assume_in_bounds!`] for more.
+///
+/// ```
+/// # fn get_index() -> usize { 0 }
+/// # fn main() {
+/// use assume::assume_in_bounds_mut;
+///
+/// let mut v = vec![1, 2, 3];
+///
+/// // Some computed index that, per invariants, is always in bounds.
+/// let i = get_index();
+///
+/// *assume_in_bounds_mut!(unsafe: v, i) += 1;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_in_bounds_mut {
+    (unsafe: $slice:expr, $index:expr $(,)?) => {{
+        $crate::__assume_in_bounds!(
+            get_mut,
+            get_unchecked_mut,
+            [&mut $slice[..]],
+            $slice,
+            $index,
+            "",
+            ""
+        )
+    }};
+    (unsafe: $slice:expr, $index:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_in_bounds!(
+            get_mut,
+            get_unchecked_mut,
+            [&mut $slice[..]],
+            $slice,
+            $index,
+            ": ",
+            $msg
+        )
+    }};
+    (unsafe: $slice:expr, $index:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_in_bounds!(
+            get_mut,
+            get_unchecked_mut,
+            [&mut $slice[..]],
+            $slice,
+            $index,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and an index");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_in_bounds {
+    (
+        $get:ident,
+        $get_unchecked:ident,
+        [$($borrow:tt)*],
+        $slice:expr,
+        $index:expr,
+        $separator:expr,
+        $msg:expr
+    ) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($($borrow)*, $index) } {
+            (slice, index) => {
+                if $crate::__assume_checked!() {
+                    #[allow(unused_imports)]
+                    use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                    let len = slice.len();
+                    match slice.$get($crate::__private::Clone::clone(&index)) {
+                        $crate::__private::Some(element) => element,
+                        $crate::__private::None => $crate::__assume_impl!(
+                            @fail $crate::__private::concat!(
+                                $crate::__private::stringify!($index),
+                                " in bounds of ",
+                                $crate::__private::stringify!($slice)
+                            ),
+                            "assumption failed: {} in bounds of {}{}{}\n  index: {:?}\n    len: {}",
+                            $crate::__private::stringify!($index),
+                            $crate::__private::stringify!($slice),
+                            $separator,
+                            $msg,
+                            (&$crate::__private::Captured(&index)).__assume_value(),
+                            len,
+                        ),
+                    }
+                } else {
+                    unsafe { slice.$get_unchecked(index) }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_element_or_subslice() {
+        let values = [1, 2, 3];
+        assert_eq!(*assume_in_bounds!(unsafe: values, 1), 2);
+        assert_eq!(assume_in_bounds!(unsafe: values, 1.., "oh no"), &[2, 3]);
+        assert_eq!(assume_in_bounds!(unsafe: &values[1..], ..1), &[2]);
+    }
+
+    #[test]
+    fn evaluates_to_mutable_element_or_subslice() {
+        let mut values = [1, 2, 3];
+        *assume_in_bounds_mut!(unsafe: values, 0) += 1;
+        assume_in_bounds_mut!(unsafe: values, 1..).copy_from_slice(&[5, 6]);
+        assert_eq!(values, [2, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 1 + 2 in bounds of values: oh no\n  index: 3\n    len: 3"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_index_and_len() {
+        let values = [1, 2, 3];
+        assume_in_bounds!(unsafe: values, 1 + 2, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "index: 2..4\n    len: 3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_range() {
+        let mut values = [1, 2, 3];
+        assume_in_bounds_mut!(unsafe: values, 2..4);
+    }
+}
@@ -0,0 +1,185 @@
+//! Type invariants.
+
+/// A condition that every value of a type satisfies between method calls.
+///
+/// Methods can then rely on it with [`assume_invariant!`]. With the `contracts` feature, this
+/// can be derived from `#[invariant(...)]` attributes, each a condition of `self`, and methods
+/// can assume the invariant on entry and check it on exit with `#[maintains_invariant]`.
+///
+/// [`assume_invariant!`]: crate::assume_invariant
+///
+/// ```
+/// use assume::Invariant;
+///
+/// struct Span {
+///     start: usize,
+///     end: usize,
+/// }
+///
+/// impl Invariant for Span {
+///     fn holds(&self) -> bool {
+///         self.start <= self.end
+///     }
+/// }
+/// ```
+pub trait Invariant {
+    /// Returns whether the invariant holds.
+    fn holds(&self) -> bool;
+
+    /// Returns the first clause of the invariant that does not hold, for checked failures.
+    #[doc(hidden)]
+    fn __assume_failing_clause(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Assumes that the invariant of the given value holds.
+///
+/// In checked configurations a violation panics, naming the failing `#[invariant(...)]` clause
+/// for derived invariants. Otherwise, the optimizer may rely on the invariant. As with
+/// `assume!`, the invariant is still evaluated: one that loops over a collection is unlikely
+/// to be helpful, and may not be optimized out.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// use assume::{assume_invariant, Invariant};
+///
+/// struct Span {
+///     start: usize,
+///     end: usize,
+/// }
+///
+/// impl Invariant for Span {
+///     fn holds(&self) -> bool {
+///         self.start <= self.end
+///     }
+/// }
+///
+/// impl Span {
+///     fn len(&self) -> usize {
+///         assume_invariant!(unsafe: self);
+///         self.end - self.start  // Cannot underflow per assumption.
+///     }
+/// }
+/// # fn main() { assert_eq!(Span { start: 1, end: 3 }.len(), 2); }
+/// ```
+#[macro_export]
+macro_rules! assume_invariant {
+    (unsafe: $value:expr $(,)?) => {{
+        $crate::__assume_invariant!($value, "", "")
+    }};
+    (unsafe: $value:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_invariant!($value, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_invariant!($value, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a value with an invariant");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_invariant {
+    ($value:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { &$value } {
+            value => {
+                #[allow(unused_imports)]
+                use $crate::Invariant as _;
+
+                if $crate::__assume_checked!() {
+                    if !value.holds() {
+                        match value.__assume_failing_clause() {
+                            $crate::__private::Some(clause) => $crate::__assume_impl!(
+                                @fail $crate::__private::concat!(
+                                    $crate::__private::stringify!($value),
+                                    " holds its invariant"
+                                ),
+                                "assumption failed: {} holds its invariant{}{}\n  clause: {}",
+                                $crate::__private::stringify!($value),
+                                $separator,
+                                $msg,
+                                clause,
+                            ),
+                            $crate::__private::None => $crate::__assume_impl!(
+                                @fail $crate::__private::concat!(
+                                    $crate::__private::stringify!($value),
+                                    " holds its invariant"
+                                ),
+                                "assumption failed: {} holds its invariant{}{}",
+                                $crate::__private::stringify!($value),
+                                $separator,
+                                $msg,
+                            ),
+                        }
+                    }
+                } else {
+                    unsafe { $crate::__private::assume_unchecked(value.holds()) }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use Invariant;
+
+    struct Span {
+        start: usize,
+        end: usize,
+    }
+
+    impl Invariant for Span {
+        fn holds(&self) -> bool {
+            self.start <= self.end
+        }
+    }
+
+    struct Sorted(&'static [u32]);
+
+    impl Invariant for Sorted {
+        fn holds(&self) -> bool {
+            self.0.windows(2).all(|pair| pair[0] <= pair[1])
+        }
+
+        fn __assume_failing_clause(&self) -> Option<&'static str> {
+            if self.holds() {
+                None
+            } else {
+                Some("self.0 is sorted")
+            }
+        }
+    }
+
+    #[test]
+    fn assumes_invariant() {
+        let span = Span { start: 1, end: 3 };
+        assume_invariant!(unsafe: span);
+        assume_invariant!(unsafe: &span, "oh no");
+        assume_invariant!(unsafe: Sorted(&[1, 2, 2]), "oh no, a {}", "problem");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: span holds its invariant: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_violation() {
+        let span = Span { start: 3, end: 1 };
+        assume_invariant!(unsafe: span, "oh no");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: Sorted(&[2, 1]) holds its invariant\n  clause: self.0 is sorted"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_failing_clause() {
+        assume_invariant!(unsafe: Sorted(&[2, 1]));
+    }
+}
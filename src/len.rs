@@ -0,0 +1,346 @@
+//! Slice length assumptions.
+
+/// Assumes that the given slice has at least the given number of elements.
+///
+/// Each operand is evaluated once. In checked configurations a shorter slice panics with its
+/// length. Otherwise, indexing the slice below that length needs no bounds check.
+///
+/// Accepts the same optional message as `assume!`. See [`assume_array_ref!`] to take the
+/// elements as an array.
+///
+/// [`assume_array_ref!`]: crate::assume_array_ref
+///
+/// ```
+/// # fn get_block() -> Vec<u8> { vec![0; 16] }
+/// # fn main() {
+/// use assume::assume_len;
+///
+/// // Some block that, per invariants, always has a full header.
+/// let block = get_block();
+///
+/// assume_len!(unsafe: block, 4, "block without a header");
+/// let header = &block[..4];  // Bounds check optimized out per assumption.
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_len {
+    (unsafe: $slice:expr, $len:expr $(,)?) => {{
+        $crate::__assume_len!([&$slice[..]], $slice, $len, "", "")
+    }};
+    (unsafe: $slice:expr, $len:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_len!([&$slice[..]], $slice, $len, ": ", $msg)
+    }};
+    (unsafe: $slice:expr, $len:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_len!(
+            [&$slice[..]],
+            $slice,
+            $len,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and a length");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the length of the given slice is a multiple of the given (non-zero) number of
+/// elements.
+///
+/// Each operand is evaluated once. In checked configurations any other length panics with the
+/// length. Otherwise, the fact is stated as `slice.len() % multiple == 0`. With a constant
+/// power of two, this lets the optimizer drop the remainder loop after a vectorized (or
+/// unrolled) loop over the slice, as well as the remainder of `chunks_exact`.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_samples() -> Vec<f32> { vec![0.0; 64] }
+/// # fn main() {
+/// use assume::assume_aligned_len;
+///
+/// // Some samples that, per invariants, always come in whole frames of 16.
+/// let samples = get_samples();
+///
+/// assume_aligned_len!(unsafe: samples, 16);
+/// let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_aligned_len {
+    (unsafe: $slice:expr, $multiple:expr $(,)?) => {{
+        $crate::__assume_aligned_len!([&$slice[..]], $slice, $multiple, "", "")
+    }};
+    (unsafe: $slice:expr, $multiple:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_aligned_len!([&$slice[..]], $slice, $multiple, ": ", $msg)
+    }};
+    (unsafe: $slice:expr, $multiple:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_aligned_len!(
+            [&$slice[..]],
+            $slice,
+            $multiple,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and a multiple");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given slice has at least `N` elements, evaluating to a reference to the
+/// first `N` of them as an array.
+///
+/// `N` must be a constant. This is the assumed counterpart of `<&[T; N]>::try_from(&slice[..N])`
+/// followed by an `unwrap`: in checked configurations a shorter slice panics with its length.
+/// Otherwise, there is no length check and no panic path at all.
+///
+/// Accepts the same optional message as `assume!`. See [`assume_array_ref_mut!`] for mutable
+/// access.
+///
+/// [`assume_array_ref_mut!`]: crate::assume_array_ref_mut
+///
+/// ```
+/// # fn get_block() -> Vec<u8> { vec![0; 16] }
+/// # fn main() {
+/// use assume::assume_array_ref;
+///
+/// // Some block that, per invariants, always has a full header.
+/// let block = get_block();
+///
+/// let header: &[u8; 4] = assume_array_ref!(unsafe: block, 4);
+/// let magic = u32::from_le_bytes(*header);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_array_ref {
+    (unsafe: $slice:expr, $len:expr $(,)?) => {{
+        $crate::__assume_array_ref!([&$slice[..]], array_ref, $slice, $len, "", "")
+    }};
+    (unsafe: $slice:expr, $len:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_array_ref!([&$slice[..]], array_ref, $slice, $len, ": ", $msg)
+    }};
+    (unsafe: $slice:expr, $len:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_array_ref!(
+            [&$slice[..]],
+            array_ref,
+            $slice,
+            $len,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and a length");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given slice has at least `N` elements, evaluating to a mutable reference
+/// to the first `N` of them as an array.
+///
+/// See [`assume_array_ref!`] for more.
+///
+/// [`assume_array_ref!`]: crate::assume_array_ref
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_array_ref_mut;
+///
+/// let mut block = vec![0u8; 16];
+///
+/// *assume_array_ref_mut!(unsafe: block, 4) = 0xcafef00du32.to_le_bytes();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_array_ref_mut {
+    (unsafe: $slice:expr, $len:expr $(,)?) => {{
+        $crate::__assume_array_ref!([&mut $slice[..]], array_mut, $slice, $len, "", "")
+    }};
+    (unsafe: $slice:expr, $len:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_array_ref!([&mut $slice[..]], array_mut, $slice, $len, ": ", $msg)
+    }};
+    (unsafe: $slice:expr, $len:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_array_ref!(
+            [&mut $slice[..]],
+            array_mut,
+            $slice,
+            $len,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a slice and a length");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_len {
+    ([$($borrow:tt)*], $slice:expr, $len:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($($borrow)*, $len) } {
+            (slice, len) => {
+                $crate::__assume_impl!(
+                    @check slice.len() >= len,
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($slice),
+                        ".len() >= ",
+                        $crate::__private::stringify!($len)
+                    ),
+                    "assumption failed: {}.len() >= {}{}{}\n  len: {}",
+                    $crate::__private::stringify!($slice),
+                    $crate::__private::stringify!($len),
+                    $separator,
+                    $msg,
+                    slice.len(),
+                );
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_aligned_len {
+    ([$($borrow:tt)*], $slice:expr, $multiple:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($($borrow)*, $multiple) } {
+            (slice, multiple) => {
+                $crate::__assume_impl!(
+                    @check slice.len() % multiple == 0,
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($slice),
+                        ".len() is a multiple of ",
+                        $crate::__private::stringify!($multiple)
+                    ),
+                    "assumption failed: {}.len() is a multiple of {}{}{}\n  len: {}",
+                    $crate::__private::stringify!($slice),
+                    $crate::__private::stringify!($multiple),
+                    $separator,
+                    $msg,
+                    slice.len(),
+                );
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_array_ref {
+    (
+        [$($borrow:tt)*],
+        $array:ident,
+        $slice:expr,
+        $len:expr,
+        $separator:expr,
+        $msg:expr
+    ) => {
+        #[allow(unused_unsafe, unused_comparisons)]
+        match unsafe { $($borrow)* } {
+            slice => {
+                $crate::__assume_impl!(
+                    @check slice.len() >= $len,
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($slice),
+                        ".len() >= ",
+                        $crate::__private::stringify!($len)
+                    ),
+                    "assumption failed: {}.len() >= {}{}{}\n  len: {}",
+                    $crate::__private::stringify!($slice),
+                    $crate::__private::stringify!($len),
+                    $separator,
+                    $msg,
+                    slice.len(),
+                );
+                unsafe { $crate::__private::$array::<_, { $len }>(slice) }
+            }
+        }
+    };
+}
+
+/// Reinterprets the start of a slice with at least `N` elements as an array.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn __array_ref<T, const N: usize>(slice: &[T]) -> &[T; N] {
+    &*(slice.as_ptr() as *const [T; N])
+}
+
+/// Reinterprets the start of a slice with at least `N` elements as a mutable array.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn __array_mut<T, const N: usize>(slice: &mut [T]) -> &mut [T; N] {
+    &mut *(slice.as_mut_ptr() as *mut [T; N])
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn accepts_long_enough_slices() {
+        let values = [1, 2, 3];
+        assume_len!(unsafe: values, 3);
+        assume_len!(unsafe: &values[1..], 1 + 1, "oh no");
+        assume_len!(unsafe: values, 0, "oh no, a {}", "problem");
+    }
+
+    #[test]
+    fn accepts_lengths_that_are_multiples() {
+        let values = [0u8; 24];
+        assume_aligned_len!(unsafe: values, 8);
+        assume_aligned_len!(unsafe: &values[..0], 16, "oh no");
+        assume_aligned_len!(unsafe: values[4..], 4, "oh no, a {}", "problem");
+    }
+
+    #[test]
+    fn evaluates_to_array_prefix() {
+        let values = [1, 2, 3];
+        assert_eq!(assume_array_ref!(unsafe: values, 2), &[1, 2]);
+        assert_eq!(assume_array_ref!(unsafe: &values[1..], 2, "oh no"), &[2, 3]);
+        assert_eq!(assume_array_ref!(unsafe: values, 0), &[0; 0]);
+    }
+
+    #[test]
+    fn evaluates_to_mutable_array_prefix() {
+        let mut values = [1, 2, 3];
+        *assume_array_ref_mut!(unsafe: values, 2) = [5, 6];
+        assume_array_ref_mut!(unsafe: values[1..], 2)[1] += 1;
+        assert_eq!(values, [5, 6, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: values.len() >= 4: oh no\n  len: 3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_short_slice() {
+        let values = [1, 2, 3];
+        assume_len!(unsafe: values, 4, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: values.len() >= 4\n  len: 3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_short_array_prefix() {
+        let mut values = [1, 2, 3];
+        assume_array_ref_mut!(unsafe: values, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: values.len() is a multiple of 16\n  len: 24")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_len_that_is_not_a_multiple() {
+        let values = [0u8; 24];
+        assume_aligned_len!(unsafe: values, 16);
+    }
+}
@@ -122,43 +122,215 @@
 //!   the return type is `()` and not `!`. This can result in warnings or errors if e.g. other
 //!   branches evaluate to a type other than `()`. Use `assume!(unsafe: @unreachable)` instead.
 //!
+//! # Checking policy
+//! By default, assumptions are checked if and only if `debug_assertions` are enabled. This can
+//! be overridden for an entire build:
+//!
+//! - The `always-check` feature (or `--cfg assume_checked`) checks every assumption, whatever
+//!   the profile. Because cargo unifies features, enabling this in a single downstream crate
+//!   turns on checking for every use of `assume!` across the dependency graph. This is useful
+//!   for hardened or canary builds.
+//!
+//! - The `unchecked-in-debug` feature (or `--cfg assume_unchecked`) never checks assumptions,
+//!   even when `debug_assertions` are enabled. This is useful for debug-profile benchmarks or
+//!   games that are too slow with every check on. The hints are still given to the optimizer,
+//!   so a debug profile with e.g. `opt-level = 1` still elides the guarded checks. See
+//!   `examples/debug_hints.rs`.
+//!
+//! These two policies contradict each other, and enabling both is a compile error.
+//!
+//! # Violation handler
+//! With the `violation-handler` feature, a handler installed with `set_violation_handler` is
+//! called with an `AssumptionFailure` (location, condition and message) whenever a checked
+//! assumption fails, just before it panics. This works in `no_std` and with `panic = "abort"`,
+//! and can be used to e.g. route violations to a log, or to abort before the panic hook or any
+//! destructor runs. Calling the handler is not `const`, so with this feature `assume!` cannot
+//! be used in `const` contexts.
+//!
+//! # Operand capture
+//! With the `capture-operands` feature, a failed checked assumption also reports the values
+//! of the operands of each comparison in its condition, and which `&&` clause failed (or, for
+//! `||`, every clause). Operands that do not implement `Debug` are reported as such. Unchecked
+//! code generation is unaffected. Integer, `bool` and `char` operands are written without
+//! `core::fmt`, so `assume!` stays usable in `const` contexts (as far as the other features
+//! allow), with the exception of comparisons of floating-point operands. Long reports are cut
+//! short at 512 bytes.
+//!
+//! ```text
+//! assumption failed: i < v.len() && v[i] != 0
+//!   failed: i < v.len()
+//!     left: 5
+//!    right: 3
+//! ```
+//!
+//! Conditions of more than 32 tokens are reported by their text alone, followed by a note that
+//! their operands were not captured, as walking them token by token would run into the
+//! recursion limit.
+//!
+//! ```text
+//! assumption failed: a.len() + b.len() + c.len() + ... == total
+//!   (operands not captured: the condition is longer than 32 tokens)
+//! ```
+//!
+//! # Panic payload
+//! A failed checked assumption panics with its message, like `assert!`. With the `std` feature,
+//! it panics with an `AssumptionViolated` (location, condition and message) instead, which
+//! `catch_unwind` callers get back by downcasting the payload. That payload is not a string, so
+//! the default panic hook prints `Box<dyn Any>` for it, and `#[should_panic(expected = "...")]`
+//! does not match it: print or compare its `Display` text instead, from a panic hook or after
+//! `catch_unwind`. Panicking with it is not `const`, so with this feature `assume!` cannot be
+//! used in `const` contexts.
+//!
+//! # Contracts
+//! With the `contracts` feature, functions can state their assumptions as attributes.
+//! `#[requires(cond)]` assumes the condition on entry, and `#[ensures(|ret| cond)]` assumes
+//! the condition on a reference to the return value before it is returned, which provides the
+//! hint to inlined callers automatically. Checked failures name the clause and function.
+//!
+//! ```text
+//! #[inline]
+//! #[requires(i < v.len())]
+//! #[ensures(|ret| *ret != 0)]
+//! fn get_divisor(v: &[u32], i: usize) -> u32 { /* ... */ }
+//! ```
+//!
+//! ```text
+//! assumption failed: *ret != 0: postcondition of `get_divisor`
+//! ```
+//!
+//! # Invariants
+//! A type's invariant can be stated once by implementing `Invariant`, and assumed by its
+//! methods with `assume_invariant!(unsafe: self)`. With the `contracts` feature, the trait can
+//! be derived from `#[invariant(...)]` conditions of `self`, and `#[maintains_invariant]`
+//! assumes the invariant on entry to a method and checks it again on exit. Checked failures
+//! name the clause that does not hold.
+//!
+//! # Refined values
+//! `Refined<T, P>` is a value that satisfies the predicate `P`, such as `Lt<16>`,
+//! `InRange<A, B>`, `NonEmpty`, `Sorted`, or one of your own. It is checked once when created,
+//! and every `get()` assumes it again, so uses need no `assume!` of their own.
+//!
+//! ```text
+//! fn lookup(table: &[u32; 16], index: Refined<usize, Lt<16>>) -> u32 {
+//!     table[*index.get()]  // Bounds check optimized out per assumption.
+//! }
+//! ```
+//!
+//! `Bounded<T, MIN, MAX>` is the refined integer from `MIN` to `MAX`, inclusive. A
+//! `Bounded<usize, MIN, MAX>` indexes a `[T; N]` with no bounds check (failing to compile
+//! unless `MAX < N`), and `wrapping_add` and `wrapping_sub` stay within the bounds.
+//!
+//! # Branded indices
+//! `brand(&slice, |s| ...)` calls the closure with the slice branded by a lifetime unique to
+//! the call. An `Index` of the brand comes only from `s.check(i)` or `s.assume(i)`, so `s[i]`
+//! needs no bounds check, and an index of one slice cannot be used with another.
+//!
+//! # Release backend
+//! Unchecked assumptions are handed to the optimizer with `core::hint::assert_unchecked` on
+//! compilers that have it (Rust 1.81 and up), which the build script detects and reports by
+//! setting `--cfg assume_assert_unchecked` for this crate. On older compilers, they branch to
+//! `core::hint::unreachable_unchecked` instead. Either way the optimizer learns the same facts,
+//! but `assert_unchecked` tends to survive more optimization passes. Likewise, unchecked shifts
+//! of `assume_no_overflow!` use `unchecked_shl` from Rust 1.93 (`--cfg assume_unchecked_shl`).
+//!
 #![doc(html_root_url = "https://docs.rs/assume/0.5.0")]
 #![no_std]
 
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+mod arith;
+mod bounded;
+mod brand;
+mod capture;
+mod cast;
+mod cmp;
+mod float;
+mod index;
+mod invariant;
+mod len;
+mod num;
+mod pattern;
+mod ptr;
+mod range;
+mod refined;
+mod text;
+mod unwrap;
+
+#[cfg(feature = "contracts")]
+mod contract;
+#[cfg(any(feature = "std", feature = "violation-handler"))]
+mod failure;
+
+pub use bounded::Bounded;
+pub use brand::{brand, Branded, Index};
+pub use invariant::Invariant;
+pub use refined::{InRange, Lt, NonEmpty, Predicate, Refined, Sorted};
+
+#[cfg(feature = "violation-handler")]
+pub use failure::{set_violation_handler, AssumptionFailure};
+
+#[cfg(feature = "std")]
+pub use failure::AssumptionViolated;
+
+#[cfg(feature = "contracts")]
+extern crate assume_macros;
+
+#[cfg(feature = "contracts")]
+pub use assume_macros::{ensures, maintains_invariant, requires, Invariant};
+
 /// Assumes that the given condition is true.
 ///
 /// This macro allows the expression of invariants in code. For example, one might `assume!`
 /// that an index is in bounds prior to indexing into a slice - this would allow the optimizer
 /// to remove the bounds checking entirely, under the promises of assume. In `debug_assertion`
-/// configurations the expression is checked. Otherwise, it is unchecked (but present).
+/// configurations the expression is checked. Otherwise, it is unchecked (but present). See the
+/// module level documentation for overriding this policy.
 ///
 /// Use `@unreachable` as the condition to assume the code path cannot be reached.
 ///
+/// Two range forms are accepted in place of a boolean condition: `lo <= x < hi` (either
+/// comparison may be `<` or `<=`), and `x in range` for any range type. In both, `x` is
+/// evaluated once and a checked failure reports its value and the range.
+/// ```
+/// # use assume::assume;
+/// # let (i, len) = (1, 3);
+/// assume!(unsafe: 0 < i && i < len);
+/// assume!(unsafe: 0 < i < len);
+/// assume!(unsafe: i in 1..len, "index {} out of range", i);
+/// ```
+///
+/// In checked builds a failure reports the stringified condition, followed by the custom
+/// message if one is given. A literal message keeps `assume!` usable in a `const fn`.
+///
 /// Because this expresses unchecked information, the act of assuming is inherently unsafe.
 /// The safe (i.e., runtime checked) alternative to this is the [`assert!`] macro. If the
 /// condition is `@unreachable`, the safe alternative to this is the [`unreachable!`] macro.
 ///
 /// See the module level documentation for more.
-/// ```
 #[macro_export]
 macro_rules! assume {
-    (unsafe: $cond:expr $(,)?) => {{
+    (unsafe: @unreachable $(,)?) => {{
+        $crate::__assume_impl!(@unreachable, "assumption failed: unreachable")
+    }};
+    (unsafe: @unreachable, $fmt:literal $(,)?) => {{
         $crate::__assume_impl!(
-            $cond,
-            $crate::__private::concat!(
-                "assumption failed: ",
-                $crate::__private::stringify!($cond)
-            )
+            @unreachable,
+            $crate::__private::concat!("assumption failed: unreachable: ", $fmt)
         )
     }};
-    (unsafe: $cond:expr, $fmt:expr $(, $($args:tt)*)?) => {{
-        $crate::__assume_impl!($cond, $fmt, $($($args)*)?)
+    (unsafe: @unreachable, $msg:expr $(,)?) => {{
+        $crate::__assume_impl!(@unreachable, "assumption failed: unreachable: {}", $msg)
     }};
-    (unsafe: @unreachable $(,)?) => {{
-        $crate::__assume_impl!(@unreachable, "assumption failed: unreachable")
+    (unsafe: @unreachable, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_impl!(
+            @unreachable,
+            "assumption failed: unreachable: {}",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
     }};
-    (unsafe: @unreachable, $fmt:expr $(, $($args:tt)*)?) => {{
-        $crate::__assume_impl!(@unreachable, $fmt, $($($args)*)?)
+    (unsafe: $($tokens:tt)+) => {{
+        $crate::__assume_split!(@munch [] [] [cmp [] []] {$($tokens)+} $($tokens)+)
     }};
     (unsafe: $($_:tt)*) => {{
         $crate::__private::compile_error!("assumption must be an expression or @unreachable");
@@ -168,24 +340,319 @@ macro_rules! assume {
     }};
 }
 
+/// Splits the condition from the message at the first top-level comma, skipping
+/// the commas of turbofish generic arguments.
+///
+/// The remaining tokens are carried twice: once in braces to match against, and once
+/// to take the original tokens from, which keeps the stringified condition intact.
+///
+/// The same pass looks for the range forms of the condition (see `__assume_range!`),
+/// tracking them in the third bracket: `[cmp [segments] [operators]]` while the
+/// condition could still be a chained comparison, `[in $in [value]]` once a top-level
+/// `in` was found, and `[no]` otherwise. The first bracket collects the tokens of the
+/// current segment, the range, or the whole condition, respectively.
+///
+/// Each step takes up to four tokens, stopping short of any that may need one of the
+/// other rules, so that a condition of a few hundred tokens stays within the recursion
+/// limit.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_split {
+    (@munch $acc:tt [] $scan:tt {, $($m:tt)*} $comma:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@done (, $($rest)*) $acc $scan)
+    };
+    (@munch [$($acc:tt)*] [] [cmp [$([$($seg:tt)*])*] [$($op:tt)*]] {:: < $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(
+            @munch [$($($seg)* $op)* $($acc)* $a $b] [<] [no] {$($m)*} $($rest)*
+        )
+    };
+    (@munch [$($acc:tt)*] [$($depth:tt)*] $scan:tt {:: < $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [$($depth)* <] $scan {$($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [$($depth:tt)+] $scan:tt {< $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [$($depth)* <] $scan {$($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [$_:tt $($depth:tt)*] $scan:tt {> $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [$($depth)*] $scan {$($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [$_:tt $__:tt $($depth:tt)*] $scan:tt {>> $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [$($depth)*] $scan {$($m)*} $($rest)*)
+    };
+    (@munch [$($x:tt)+] [] [cmp [] []] {in $($m:tt)*} $in:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [] [] [in $in [$($x)+]] {$($m)*} $($rest)*)
+    };
+    (@munch [$($cur:tt)+] [] [cmp [$($seg:tt)*] [$($op:tt)*]] {< $($m:tt)*} $lt:tt $($rest:tt)*) => {
+        $crate::__assume_split!(
+            @munch [] [] [cmp [$($seg)* [$($cur)+]] [$($op)* $lt]] {$($m)*} $($rest)*
+        )
+    };
+    (@munch [$($cur:tt)+] [] [cmp [$($seg:tt)*] [$($op:tt)*]] {<= $($m:tt)*} $le:tt $($rest:tt)*) => {
+        $crate::__assume_split!(
+            @munch [] [] [cmp [$($seg)* [$($cur)+]] [$($op)* $le]] {$($m)*} $($rest)*
+        )
+    };
+    (@munch $acc:tt [] [cmp $segs:tt $ops:tt] {&& $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_split!(@no $acc $segs $ops {&& $($m)*} $($rest)*)
+    };
+    (@munch $acc:tt [] [cmp $segs:tt $ops:tt] {|| $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_split!(@no $acc $segs $ops {|| $($m)*} $($rest)*)
+    };
+    (@munch $acc:tt [] [cmp $segs:tt $ops:tt] {.. $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_split!(@no $acc $segs $ops {.. $($m)*} $($rest)*)
+    };
+    (@munch $acc:tt [] [cmp $segs:tt $ops:tt] {..= $($m:tt)*} $($rest:tt)*) => {
+        $crate::__assume_split!(@no $acc $segs $ops {..= $($m)*} $($rest)*)
+    };
+    // Past the range forms, only commas and turbofish matter, so longer steps are taken.
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt , $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt :: $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt , $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt :: $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt , $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt :: $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt , $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt :: $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt , $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt :: $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt , $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e $f] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt :: $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e $f] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt $_7:tt , $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $g:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e $f $g] [] [no] {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt $_7:tt :: $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $g:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e $f $g] [] [no] {:: $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] [no] {$_1:tt $_2:tt $_3:tt $_4:tt $_5:tt $_6:tt $_7:tt $_8:tt $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt $g:tt $h:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d $e $f $g $h] [] [no] {$($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt , $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt :: < $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {:: < $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt < $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {< $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt <= $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {<= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt in $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {in $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt && $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {&& $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt || $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {|| $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt .. $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {.. $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt ..= $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] [] $scan {..= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt , $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt :: < $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {:: < $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt < $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {< $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt <= $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {<= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt in $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {in $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt && $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {&& $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt || $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {|| $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt .. $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {.. $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt ..= $($m:tt)*} $a:tt $b:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b] [] $scan {..= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt , $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {, $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt :: < $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {:: < $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt < $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {< $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt <= $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {<= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt in $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {in $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt && $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {&& $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt || $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {|| $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt .. $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {.. $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt ..= $($m:tt)*} $a:tt $b:tt $c:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c] [] $scan {..= $($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] [] $scan:tt {$_1:tt $_2:tt $_3:tt $_4:tt $($m:tt)*} $a:tt $b:tt $c:tt $d:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a $b $c $d] [] $scan {$($m)*} $($rest)*)
+    };
+    (@munch [$($acc:tt)*] $depth:tt $scan:tt {$_:tt $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($acc)* $a] $depth $scan {$($m)*} $($rest)*)
+    };
+    (@munch $acc:tt $depth:tt $scan:tt {}) => {
+        $crate::__assume_split!(@done () $acc $scan)
+    };
+
+    // Gives up on the range forms, joining the segments back into the condition.
+    (@no [$($acc:tt)*] [$([$($seg:tt)*])*] [$($op:tt)*] {$_:tt $($m:tt)*} $a:tt $($rest:tt)*) => {
+        $crate::__assume_split!(@munch [$($($seg)* $op)* $($acc)* $a] [] [no] {$($m)*} $($rest)*)
+    };
+
+    (@done $msg:tt [$($acc:tt)*] [cmp [$([$($seg:tt)*])*] [$($op:tt)*]]) => {
+        $crate::__assume_range!(
+            @done $msg [$($($seg)* $op)* $($acc)*] [cmp [$([$($seg)*])*] [$($op)*] [$($acc)*]]
+        )
+    };
+    (@done $msg:tt $acc:tt $scan:tt) => {
+        $crate::__assume_range!(@done $msg $acc $scan)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_cond {
+    ([$cond:expr] [$($raw:tt)*] $(,)?) => {
+        $crate::__assume_check!(
+            $cond,
+            [$($raw)*],
+            $crate::__private::concat!(
+                "assumption failed: ",
+                $crate::__private::stringify!($cond)
+            )
+        )
+    };
+    ([$cond:expr] [$($raw:tt)*], $fmt:literal $(,)?) => {
+        // Joined at compile time, which keeps assume! as const as panic!/assert!.
+        $crate::__assume_check!(
+            $cond,
+            [$($raw)*],
+            $crate::__private::concat!(
+                "assumption failed: ",
+                $crate::__private::stringify!($cond),
+                ": ",
+                $fmt
+            )
+        )
+    };
+    ([$cond:expr] [$($raw:tt)*], $msg:expr $(,)?) => {
+        $crate::__assume_check!(
+            $cond,
+            [$($raw)*],
+            "assumption failed: {}: {}",
+            $crate::__private::stringify!($cond),
+            $msg
+        )
+    };
+    ([$cond:expr] [$($raw:tt)*], $fmt:expr, $($args:tt)*) => {
+        $crate::__assume_check!(
+            $cond,
+            [$($raw)*],
+            "assumption failed: {}: {}",
+            $crate::__private::stringify!($cond),
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    ($($_:tt)*) => {
+        $crate::__private::compile_error!("assumption must be an expression or @unreachable")
+    };
+}
+
+#[cfg(not(feature = "capture-operands"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_check {
+    ($cond:expr, [$($raw:tt)*], $($message:tt)*) => {
+        $crate::__assume_impl!($cond, $($message)*)
+    };
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __assume_impl {
     ($cond:expr, $fmt:expr $(, $($args:tt)*)?) => {{
+        $crate::__assume_impl!(
+            @check $cond,
+            $crate::__private::stringify!($cond),
+            $fmt,
+            $($($args)*)?
+        )
+    }};
+    (@unreachable, $fmt:expr $(, $($args:tt)*)?) => {{
+        $crate::__assume_impl!(@fail "unreachable", $fmt, $($($args)*)?)
+    }};
+    (@check $cond:expr, $condition:expr, $fmt:expr $(, $($args:tt)*)?) => {{
         #[allow(unused_unsafe)]
-        if unsafe { !$cond } {
-            $crate::__assume_impl!(@unreachable, $fmt, $($($args)*)?)
+        let holds: bool = unsafe { $cond };
+        if $crate::__assume_checked!() {
+            if !holds {
+                $crate::__assume_impl!(@fail $condition, $fmt, $($($args)*)?);
+            }
+        } else {
+            unsafe { $crate::__private::assume_unchecked(holds) }
         }
     }};
-    (@unreachable, $fmt:expr $(, $($args:tt)*)?) => {{
-        if $crate::__private::cfg!(debug_assertions) {
-            // Panic cannot accept non-const format strings, which means we cannot
-            // arbitrarily augment this message with more detail. Instead, we behave
-            // like assert!: the default message is the code, but a provided format
-            // string replaces this entirely if provided.
-            //
-            // This makes assume! as const as panic!/assert!.
-            $crate::__private::panic!($fmt, $($($args)*)?);
+    (@fail $condition:expr, $fmt:expr $(,)?) => {{
+        if $crate::__assume_checked!() {
+            // Like panic!, a lone message is not a format string.
+            $crate::__assume_fail!($condition, $fmt)
+        } else {
+            unsafe {
+                $crate::__private::unreachable_unchecked()
+            }
+        }
+    }};
+    (@fail $condition:expr, $fmt:expr, $($args:tt)+) => {{
+        if $crate::__assume_checked!() {
+            match $crate::__private::format_args!($fmt, $($args)+) {
+                message => $crate::__assume_fail!($condition, message),
+            }
         } else {
             unsafe {
                 $crate::__private::unreachable_unchecked()
@@ -194,10 +661,89 @@ macro_rules! __assume_impl {
     }};
 }
 
+/// Reports the failure, which is done outside of `const` contexts, then panics.
+#[cfg(any(feature = "std", feature = "violation-handler"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_fail {
+    ($condition:expr, $message:expr) => {
+        $crate::__private::fail($condition, $crate::__private::format_args!("{}", $message))
+    };
+}
+
+#[cfg(not(any(feature = "std", feature = "violation-handler")))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_fail {
+    ($condition:expr, $message:expr) => {
+        $crate::__private::panic!("{}", $message)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_checked {
+    () => {
+        $crate::__private::ALWAYS_CHECK
+            || (!$crate::__private::NEVER_CHECK && $crate::__private::cfg!(debug_assertions))
+    };
+}
+
+#[cfg(all(
+    any(feature = "always-check", assume_checked),
+    any(feature = "unchecked-in-debug", assume_unchecked)
+))]
+compile_error!(
+    "assume: `always-check` (or `--cfg assume_checked`) cannot be combined with \
+     `unchecked-in-debug` (or `--cfg assume_unchecked`)"
+);
+
 /// Used by macros.
 #[doc(hidden)]
 pub mod __private {
-    pub use core::{cfg, compile_error, concat, hint::unreachable_unchecked, panic, stringify};
+    pub use core::{
+        cfg, column, compile_error, concat, file, format_args, hint::unreachable_unchecked, line,
+        panic, stringify,
+    };
+    pub use core::{
+        clone::Clone, cmp::Ordering, convert::TryFrom, num::NonZero, ops::RangeBounds,
+        option::Option, option::Option::None, option::Option::Some, ptr::NonNull,
+        result::Result::Err, result::Result::Ok, str::from_utf8, str::from_utf8_unchecked,
+    };
+
+    pub use capture::{write_message, Captured, CapturedDebug, CapturedOpaque, Message};
+    pub use len::{__array_mut as array_mut, __array_ref as array_ref};
+    pub use ptr::{__in_allocation as in_allocation, __is_aligned as is_aligned};
+
+    #[cfg(feature = "contracts")]
+    pub use contract::{__FromResidual as FromResidual, __Try as Try};
+    #[cfg(feature = "contracts")]
+    pub use core::ops::ControlFlow;
+
+    #[cfg(any(feature = "std", feature = "violation-handler"))]
+    pub use failure::fail;
+
+    /// Hands a condition that holds to the optimizer.
+    ///
+    /// Uses `assert_unchecked` where the compiler has it (see `build.rs`).
+    #[inline(always)]
+    pub const unsafe fn assume_unchecked(condition: bool) {
+        #[cfg(assume_assert_unchecked)]
+        core::hint::assert_unchecked(condition);
+
+        #[cfg(not(assume_assert_unchecked))]
+        if !condition {
+            unreachable_unchecked()
+        }
+    }
+
+    /// Whether assumptions are checked regardless of `debug_assertions`.
+    ///
+    /// Evaluated in this crate so that the feature applies to every caller.
+    pub const ALWAYS_CHECK: bool = cfg!(any(feature = "always-check", assume_checked));
+
+    /// Whether assumptions are unchecked regardless of `debug_assertions`.
+    pub const NEVER_CHECK: bool = cfg!(any(feature = "unchecked-in-debug", assume_unchecked));
 }
 
 #[cfg(test)]
@@ -210,6 +756,14 @@ mod tests {
         };
     }
 
+    /// Rogue macro.
+    #[allow(unused_macros)]
+    macro_rules! column {
+        ($($tt:tt)*) => {
+            return
+        };
+    }
+
     /// Rogue macro.
     #[allow(unused_macros)]
     macro_rules! concat {
@@ -218,6 +772,30 @@ mod tests {
         };
     }
 
+    /// Rogue macro.
+    #[allow(unused_macros)]
+    macro_rules! file {
+        ($($tt:tt)*) => {
+            return
+        };
+    }
+
+    /// Rogue macro.
+    #[allow(unused_macros)]
+    macro_rules! format_args {
+        ($($tt:tt)*) => {
+            return
+        };
+    }
+
+    /// Rogue macro.
+    #[allow(unused_macros)]
+    macro_rules! line {
+        ($($tt:tt)*) => {
+            return
+        };
+    }
+
     /// Rogue macro.
     #[allow(unused_macros)]
     macro_rules! panic {
@@ -244,8 +822,12 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(any(feature = "std", feature = "violation-handler")))]
     const fn fn_can_be_const() {
+        let (i, c) = (-1i8, 'a');
         assume!(unsafe: 1 > 0, "impossible");
+        assume!(unsafe: i < 0 && c == 'a');
+        assume!(unsafe: c != '\n' || i == 0, "impossible");
     }
 
     #[test]
@@ -256,45 +838,296 @@ mod tests {
         assume!(unsafe: true, "this is unused: {}", unused);
     }
 
+    #[test]
+    #[cfg(all(debug_assertions, not(assume_checks)))]
+    fn is_unchecked_in_debug() {
+        const { assert!(!__assume_checked!()) };
+
+        // Would panic if checked. Sortedness is never handed to the optimizer, so this is the
+        // one failed assumption that is harmless unchecked.
+        let unsorted: ::Refined<&[u8], ::Sorted> = unsafe { ::Refined::new_unchecked(&[2, 1]) };
+        assert_eq!(*unsorted.get(), [2, 1]);
+    }
+
     #[test]
     #[should_panic(expected = "assumption failed: 2 > 3")]
-    #[cfg(debug_assertions)]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment() {
         assume!(unsafe: 2 > 3);
     }
 
     #[test]
-    #[should_panic(expected = "oh no")]
-    #[cfg(debug_assertions)]
+    #[should_panic(expected = "assumption failed: 2 > 3: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment_with_message() {
         assume!(unsafe: 2 > 3, "oh no");
     }
 
     #[test]
-    #[should_panic(expected = "oh no, a problem")]
-    #[cfg(debug_assertions)]
+    #[should_panic(expected = "assumption failed: 2 > 3: oh no, a problem")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment_with_format() {
         assume!(unsafe: 2 > 3, "oh no, a {}", "problem");
     }
 
+    #[test]
+    #[should_panic(expected = "assumption failed: 2 > 3: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn is_not_affected_by_call_site_environment_with_message_expression() {
+        const MESSAGE: &str = "oh no";
+        assume!(unsafe: 2 > 3, MESSAGE);
+    }
+
     #[test]
     #[should_panic(expected = "assumption failed: unreachable")]
-    #[cfg(debug_assertions)]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment_unreachable() {
         assume!(unsafe: @unreachable);
     }
 
     #[test]
-    #[should_panic(expected = "oh no")]
-    #[cfg(debug_assertions)]
+    #[should_panic(expected = "assumption failed: unreachable: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment_unreachable_with_message() {
         assume!(unsafe: @unreachable, "oh no");
     }
 
     #[test]
-    #[should_panic(expected = "oh no, a problem")]
-    #[cfg(debug_assertions)]
+    #[should_panic(expected = "assumption failed: unreachable: oh no, a problem")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
     fn is_not_affected_by_call_site_environment_unreachable_with_format() {
         assume!(unsafe: @unreachable, "oh no, a {}", "problem");
     }
+
+    #[test]
+    #[cfg(all(feature = "violation-handler", assume_checks))]
+    fn violation_handler_is_called() {
+        use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static TRACKED_LINE: AtomicU32 = AtomicU32::new(0);
+
+        fn handler(failure: &::AssumptionFailure) {
+            if failure.condition() == "4 > 5" {
+                assert!(failure.file().ends_with("lib.rs"));
+                assert!(std::format!("{}", failure.message())
+                    .starts_with("assumption failed: 4 > 5: oh no, a problem"));
+                CALLS.fetch_add(1, Ordering::SeqCst);
+            } else if failure.condition() == "index < self.len()" {
+                TRACKED_LINE.store(failure.line(), Ordering::SeqCst);
+            }
+        }
+
+        ::set_violation_handler(handler);
+        let result = std::panic::catch_unwind(|| {
+            assume!(unsafe: 4 > 5, "oh no, a {}", "problem");
+        });
+
+        assert!(result.is_err());
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        // Reported where the panic is, at the caller of a `#[track_caller]` function.
+        let line = std::line!() + 3;
+        let result = std::panic::catch_unwind(|| {
+            ::brand(&[1], |values| unsafe {
+                values.assume(1);
+            })
+        });
+
+        assert!(result.is_err());
+        assert_eq!(TRACKED_LINE.load(Ordering::SeqCst), line);
+    }
+
+    #[test]
+    #[cfg(all(feature = "violation-handler", assume_checks))]
+    fn violation_handler_may_not_return() {
+        use std::env;
+        use std::process::Command;
+
+        // The handler is global, so it is installed in a run of just this test.
+        const CHILD: &str = "ASSUME_TEST_HANDLER_EXITS";
+
+        fn handler(_: &::AssumptionFailure) {
+            std::process::exit(3);
+        }
+
+        if env::var_os(CHILD).is_some() {
+            ::set_violation_handler(handler);
+            assume!(unsafe: 8 > 9);
+            unreachable!();
+        }
+
+        let output = Command::new(env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tests::violation_handler_may_not_return",
+                "--nocapture",
+            ])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+
+        // Called before the panic, so the panic hook never runs.
+        assert_eq!(output.status.code(), Some(3));
+        assert!(!std::string::String::from_utf8_lossy(&output.stderr).contains("panicked"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", assume_checks))]
+    fn violation_is_recovered_from_payload() {
+        let line = std::line!() + 2;
+        let payload = std::panic::catch_unwind(|| {
+            assume!(unsafe: 6 > 7, "oh no, a {}", "problem");
+        })
+        .unwrap_err();
+
+        let violated = payload.downcast_ref::<::AssumptionViolated>().unwrap();
+        assert_eq!(violated.condition(), "6 > 7");
+        assert!(violated
+            .message()
+            .starts_with("assumption failed: 6 > 7: oh no, a problem"));
+        assert_eq!(std::format!("{}", violated), violated.message());
+        assert_eq!(violated.location().line(), line);
+    }
+
+    #[test]
+    fn long_condition_is_within_recursion_limit() {
+        let v = [1u32; 60];
+        #[rustfmt::skip]
+        assume!(
+            unsafe: v[0] + v[1] + v[2] + v[3] + v[4] + v[5] + v[6] + v[7] + v[8] + v[9] + v[10] + v[11] + v[12] + v[13] + v[14] + v[15] + v[16] + v[17] + v[18] + v[19] + v[20] + v[21] + v[22] + v[23] + v[24] + v[25] + v[26] + v[27] + v[28] + v[29] + v[30] + v[31] + v[32] + v[33] + v[34] + v[35] + v[36] + v[37] + v[38] + v[39] + v[40] + v[41] + v[42] + v[43] + v[44] + v[45] + v[46] + v[47] + v[48] + v[49] + v[50] + v[51] + v[52] + v[53] + v[54] + v[55] + v[56] + v[57] + v[58] + v[59] == 60 && v.len() == 60,
+            "oh no, a {}",
+            "problem"
+        );
+        #[rustfmt::skip]
+        assume!(
+            unsafe: v[0] == 0 || v[1] == 0 || v[2] == 0 || v[3] == 0 || v[4] == 0 || v[5] == 0 || v[6] == 0 || v[7] == 0 || v[8] == 0 || v[9] == 0 || v[10] == 0 || v[11] == 0 || v[12] == 0 || v[13] == 0 || v[14] == 0 || v[15] == 0 || v[16] == 0 || v[17] == 0 || v[18] == 0 || v[19] == 0 || v[20] == 0 || v[21] == 0 || v[22] == 0 || v[23] == 0 || v[24] == 0 || v[25] == 0 || v[26] == 0 || v[27] == 0 || v[28] == 0 || v[29] == 0 || v[30] == 0 || v[31] == 0 || v[32] == 0 || v[33] == 0 || v[34] == 0 || v[35] == 0 || v[36] == 0 || v[37] == 0 || v[38] == 0 || v[39] == 0 || v.len() == 60,
+            "oh no"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x == 0: oh no, a problem\n  (operands not captured: the condition is longer than 32 tokens)"
+    )]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn notes_long_condition_is_not_captured() {
+        let x = 1u32;
+        assume!(
+            unsafe: x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x == 0,
+            "oh no, a {}",
+            "problem"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x == 0\n  (operands not captured: the condition is longer than 32 tokens)"
+    )]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn notes_long_condition_is_not_captured_without_message() {
+        let x = 1u32;
+        assume!(unsafe: x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x + x == 0);
+    }
+
+    #[test]
+    fn condition_can_contain_turbofish_commas() {
+        assume!(unsafe: ::std::convert::identity::<Result<u8, u8>>(Ok(1)).is_ok(), "turbofish");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: 2 + 3 < 1\n   left: 5\n  right: 1")]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn captures_comparison_operands() {
+        assume!(unsafe: 2 + 3 < 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 1 < 2 && 3 == 4: oh no\n  failed: 3 == 4\n    left: 3\n   right: 4"
+    )]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn captures_failed_conjunct() {
+        assume!(unsafe: 1 < 2 && 3 == 4, "oh no");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 2 < 1 || ::std::convert::identity(false)\n  failed: 2 < 1\n    left: 2\n   right: 1\n  failed: ::std::convert::identity(false)"
+    )]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn captures_every_disjunct() {
+        assume!(unsafe: 2 < 1 || ::std::convert::identity(false));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: i < 0 && c != '\\u{1b}'\n  failed: c != '\\u{1b}'\n    left: '\\u{1b}'\n   right: '\\u{1b}'"
+    )]
+    #[cfg(all(
+        feature = "capture-operands",
+        not(any(feature = "std", feature = "violation-handler")),
+        assume_checks
+    ))]
+    fn captures_operands_in_const_fn() {
+        const fn check(i: i8, c: char) {
+            assume!(unsafe: i < 0 && c != '\u{1b}');
+        }
+
+        check(-1, '\u{1b}');
+    }
+
+    #[test]
+    #[should_panic(expected = "   left: <not Debug>\n  right: <not Debug>")]
+    #[cfg(all(feature = "capture-operands", assume_checks, not(feature = "std")))]
+    fn captures_operands_without_debug() {
+        struct NotDebug;
+
+        impl PartialEq for NotDebug {
+            fn eq(&self, _: &NotDebug) -> bool {
+                false
+            }
+        }
+
+        assume!(unsafe: NotDebug == NotDebug);
+    }
+
+    #[test]
+    fn chain_evaluates_value_once() {
+        let mut calls = 0;
+        assume!(unsafe: 0 <= { calls += 1; calls } < 2);
+        assume!(unsafe: 1 < { calls += 1; calls } <= 2, "oh no");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn in_evaluates_value_once() {
+        let mut calls = 0;
+        assume!(unsafe: { calls += 1; calls } in 1..2);
+        assume!(unsafe: { calls += 1; calls } in ..=2, "oh no, a {}", "problem");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn ordinary_conditions_are_unaffected() {
+        let values = [1, 2, 3];
+        assume!(unsafe: 1 < values[0] + 1 && values[2] < 4);
+        assume!(unsafe: ::std::vec::Vec::<u8>::with_capacity(8).capacity() < 9);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 0 <= 1 + 2 < 3: oh no\n  value: 3\n  range: [0, 3)"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn chain_reports_value_and_range() {
+        assume!(unsafe: 0 <= 1 + 2 < 3, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: 1 + 2 in 0..3\n  value: 3\n  range: 0..3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn in_reports_value_and_range() {
+        assume!(unsafe: 1 + 2 in 0..3);
+    }
 }
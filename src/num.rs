@@ -0,0 +1,290 @@
+//! Numeric assumptions.
+
+/// Assumes that the given integer is not zero, evaluating to it as the matching [`NonZero`]
+/// type (such as `NonZeroU32` for a `u32`).
+///
+/// The value is evaluated once. In checked configurations a zero panics. Otherwise, this is
+/// [`NonZero::new_unchecked`].
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// [`NonZero`]: core::num::NonZero
+/// [`NonZero::new_unchecked`]: core::num::NonZero::new_unchecked
+///
+/// ```
+/// # fn next_id() -> u32 { 1 }
+/// # fn main() {
+/// use assume::assume_nonzero;
+/// use std::num::NonZeroU32;
+///
+/// // Some id that, per invariants, is never zero.
+/// let id = next_id();
+///
+/// let handle: Option<NonZeroU32> = Some(assume_nonzero!(unsafe: id, "ids start at one"));
+/// assert_eq!(std::mem::size_of_val(&handle), 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_nonzero {
+    (unsafe: $value:expr $(,)?) => {{
+        $crate::__assume_nonzero!($value, "", "")
+    }};
+    (unsafe: $value:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_nonzero!($value, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_nonzero!($value, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be an integer");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given unsigned integer is a power of two, evaluating to it.
+///
+/// The value is evaluated once. In checked configurations any other value panics with the
+/// value. Otherwise, the fact is stated with `is_power_of_two`, which lets the optimizer turn
+/// `x % n` into `x & (n - 1)` and `x / n` into a shift. Signed integers are rejected.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn get_capacity() -> usize { 16 }
+/// # fn main() {
+/// use assume::assume_pow2;
+///
+/// // Some ring buffer capacity that, per invariants, is always a power of two.
+/// let capacity = assume_pow2!(unsafe: get_capacity());
+///
+/// let slot = 21 % capacity;  // Computed with a mask per assumption.
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_pow2 {
+    (unsafe: $value:expr $(,)?) => {{
+        $crate::__assume_pow2!($value, "", "")
+    }};
+    (unsafe: $value:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_pow2!($value, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_pow2!($value, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be an integer");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given integer is a multiple of the given (non-zero) integer, evaluating to
+/// the former.
+///
+/// Each operand is evaluated once. In checked configurations any other value panics with the
+/// value. Otherwise, the fact is stated as `value % multiple == 0`. With a constant power of
+/// two as the multiple, this lets the optimizer drop the remainder loop after a vectorized
+/// (or unrolled) loop over that many elements.
+///
+/// Accepts the same optional message as `assume!`. See [`assume_aligned_len!`] for the common
+/// case of a slice length.
+///
+/// [`assume_aligned_len!`]: crate::assume_aligned_len
+///
+/// ```
+/// # fn get_len() -> usize { 64 }
+/// # fn main() {
+/// use assume::assume_multiple_of;
+///
+/// // Some length that, per invariants, is always a whole number of blocks.
+/// let len = assume_multiple_of!(unsafe: get_len(), 8);
+///
+/// for block in 0..len / 8 {
+///     // ...
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_multiple_of {
+    (unsafe: $value:expr, $multiple:expr $(,)?) => {{
+        $crate::__assume_multiple_of!($value, $multiple, "", "")
+    }};
+    (unsafe: $value:expr, $multiple:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_multiple_of!($value, $multiple, ": ", $msg)
+    }};
+    (unsafe: $value:expr, $multiple:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_multiple_of!(
+            $value,
+            $multiple,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be an integer and a multiple");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_nonzero {
+    ($value:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $value } {
+            value => {
+                if $crate::__assume_checked!() {
+                    match $crate::__private::NonZero::new(value) {
+                        $crate::__private::Some(value) => value,
+                        $crate::__private::None => $crate::__assume_impl!(
+                            @fail $crate::__private::concat!(
+                                $crate::__private::stringify!($value),
+                                " != 0"
+                            ),
+                            "assumption failed: {} != 0{}{}",
+                            $crate::__private::stringify!($value),
+                            $separator,
+                            $msg,
+                        ),
+                    }
+                } else {
+                    unsafe { $crate::__private::NonZero::new_unchecked(value) }
+                }
+            }
+        }
+    };
+}
+
+/// States the fact with `is_power_of_two`, which only unsigned integers have: for signed ones,
+/// `n & (n - 1) == 0` overflows at `MIN` and holds for it, which is not a power of two.
+///
+/// ```compile_fail
+/// # let value = 16i32;
+/// assume::assume_pow2!(unsafe: value);
+/// ```
+/// ```compile_fail
+/// assume::assume_pow2!(unsafe: i64::MIN);
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_pow2 {
+    ($value:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $value } {
+            value => {
+                #[allow(unused_imports)]
+                use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                $crate::__assume_impl!(
+                    @check value.is_power_of_two(),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($value),
+                        " is a power of two"
+                    ),
+                    "assumption failed: {} is a power of two{}{}\n  value: {:?}",
+                    $crate::__private::stringify!($value),
+                    $separator,
+                    $msg,
+                    (&$crate::__private::Captured(&value)).__assume_value(),
+                );
+                value
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_multiple_of {
+    ($value:expr, $multiple:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($value, $multiple) } {
+            (value, multiple) => {
+                #[allow(unused_imports)]
+                use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                $crate::__assume_impl!(
+                    @check value % multiple == 0,
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($value),
+                        " is a multiple of ",
+                        $crate::__private::stringify!($multiple)
+                    ),
+                    "assumption failed: {} is a multiple of {}{}{}\n  value: {:?}",
+                    $crate::__private::stringify!($value),
+                    $crate::__private::stringify!($multiple),
+                    $separator,
+                    $msg,
+                    (&$crate::__private::Captured(&value)).__assume_value(),
+                );
+                value
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::{NonZeroI8, NonZeroU32};
+
+    #[test]
+    fn evaluates_to_nonzero() {
+        let value = 7u32;
+        let nonzero: NonZeroU32 = assume_nonzero!(unsafe: value);
+        assert_eq!(nonzero.get(), 7);
+        let nonzero: NonZeroI8 = assume_nonzero!(unsafe: -1, "oh no");
+        assert_eq!(nonzero.get(), -1);
+        assert_eq!(
+            assume_nonzero!(unsafe: value * 2, "oh no, a {}", "problem").get(),
+            14
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value - 7 != 0: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_zero() {
+        let value = 7u64;
+        assume_nonzero!(unsafe: value - 7, "oh no");
+    }
+
+    #[test]
+    fn evaluates_to_power_of_two() {
+        let value = 16usize;
+        assert_eq!(assume_pow2!(unsafe: value), 16);
+        assert_eq!(assume_pow2!(unsafe: 1u8, "oh no"), 1);
+        assert_eq!(
+            assume_pow2!(unsafe: value * 2, "oh no, a {}", "problem"),
+            32
+        );
+    }
+
+    #[test]
+    fn evaluates_to_multiple() {
+        let value = 24usize;
+        assert_eq!(assume_multiple_of!(unsafe: value, 8), 24);
+        assert_eq!(assume_multiple_of!(unsafe: value, 3, "oh no"), 24);
+        assert_eq!(assume_multiple_of!(unsafe: 0u32, 7), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value is a power of two: oh no\n  value: 0")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_zero_as_not_a_power_of_two() {
+        let value = 0u32;
+        assume_pow2!(unsafe: value, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value + 1 is a multiple of 8\n  value: 25")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_value_that_is_not_a_multiple() {
+        let value = 24usize;
+        assume_multiple_of!(unsafe: value + 1, 8);
+    }
+}
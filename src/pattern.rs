@@ -0,0 +1,227 @@
+//! Pattern assumptions.
+
+/// Assumes that the given expression matches a refutable pattern, binding its variables.
+///
+/// This behaves like `let ... else`: the pattern's bindings are introduced into the
+/// surrounding scope. If the pattern does not match, the path is handled as with
+/// `assume!(unsafe: @unreachable)`: checked configurations panic, otherwise the
+/// mismatch is assumed to be impossible.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_let;
+///
+/// enum Choices {
+///     This(u32),
+///     That,
+///     Other,
+/// }
+/// # fn get_choice() -> Choices { Choices::This(1) }
+///
+/// // Some choice that, per invariants, is always This.
+/// let choice = get_choice();
+///
+/// assume_let!(unsafe: Choices::This(value) = choice, "choice was not this");
+/// println!("{}", value);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_let {
+    (unsafe: $pat:pat = $expr:expr $(,)?) => {
+        $crate::__assume_let!(
+            $pat = $expr,
+            $crate::__private::concat!(
+                "assumption failed: let ",
+                $crate::__private::stringify!($pat),
+                " = ",
+                $crate::__private::stringify!($expr)
+            )
+        );
+    };
+    (unsafe: $pat:pat = $expr:expr, $fmt:literal $(,)?) => {
+        $crate::__assume_let!(
+            $pat = $expr,
+            $crate::__private::concat!(
+                "assumption failed: let ",
+                $crate::__private::stringify!($pat),
+                " = ",
+                $crate::__private::stringify!($expr),
+                ": ",
+                $fmt
+            )
+        );
+    };
+    (unsafe: $pat:pat = $expr:expr, $msg:expr $(,)?) => {
+        $crate::__assume_let!(
+            $pat = $expr,
+            "assumption failed: let {} = {}: {}",
+            $crate::__private::stringify!($pat),
+            $crate::__private::stringify!($expr),
+            $msg
+        );
+    };
+    (unsafe: $pat:pat = $expr:expr, $fmt:expr, $($args:tt)*) => {
+        $crate::__assume_let!(
+            $pat = $expr,
+            "assumption failed: let {} = {}: {}",
+            $crate::__private::stringify!($pat),
+            $crate::__private::stringify!($expr),
+            $crate::__private::format_args!($fmt, $($args)*)
+        );
+    };
+    (unsafe: $($_:tt)*) => {
+        $crate::__private::compile_error!("assumption must be of the form `pattern = expression`");
+    };
+    ($($_:tt)*) => {
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    };
+}
+
+/// Assumes that the given expression matches one of the given patterns.
+///
+/// This is the `assume!` form of [`matches!`], including an optional `if` guard. This
+/// removes the need for a `match` with an `@unreachable` arm when only the fact that the
+/// value matches is of interest.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_matches;
+///
+/// enum Choices {
+///     This,
+///     That,
+///     Other,
+/// }
+/// # fn get_choice() -> Choices { Choices::This }
+///
+/// // Some choice that, per invariants, is never Other.
+/// let choice = get_choice();
+///
+/// assume_matches!(unsafe: choice, Choices::This | Choices::That, "choice was other");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_matches {
+    (unsafe: $expr:expr, $($pat:pat)|+ $(if $guard:expr)? $(,)?) => {{
+        $crate::__assume_matches!(
+            $expr,
+            [$($pat)|+ $(if $guard)?],
+            $crate::__private::concat!(
+                "assumption failed: matches!(",
+                $crate::__private::stringify!($expr),
+                ", ",
+                $crate::__private::stringify!($($pat)|+ $(if $guard)?),
+                ")"
+            )
+        )
+    }};
+    (unsafe: $expr:expr, $($pat:pat)|+ $(if $guard:expr)?, $fmt:literal $(,)?) => {{
+        $crate::__assume_matches!(
+            $expr,
+            [$($pat)|+ $(if $guard)?],
+            $crate::__private::concat!(
+                "assumption failed: matches!(",
+                $crate::__private::stringify!($expr),
+                ", ",
+                $crate::__private::stringify!($($pat)|+ $(if $guard)?),
+                "): ",
+                $fmt
+            )
+        )
+    }};
+    (unsafe: $expr:expr, $($pat:pat)|+ $(if $guard:expr)?, $msg:expr $(,)?) => {{
+        $crate::__assume_matches!(
+            $expr,
+            [$($pat)|+ $(if $guard)?],
+            "assumption failed: matches!({}, {}): {}",
+            $crate::__private::stringify!($expr),
+            $crate::__private::stringify!($($pat)|+ $(if $guard)?),
+            $msg
+        )
+    }};
+    (unsafe: $expr:expr, $($pat:pat)|+ $(if $guard:expr)?, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_matches!(
+            $expr,
+            [$($pat)|+ $(if $guard)?],
+            "assumption failed: matches!({}, {}): {}",
+            $crate::__private::stringify!($expr),
+            $crate::__private::stringify!($($pat)|+ $(if $guard)?),
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be of the form `expression, pattern`");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_let {
+    ($pat:pat = $expr:expr, $($message:tt)*) => {
+        #[allow(unused_unsafe)]
+        let $pat = (unsafe { $expr }) else {
+            $crate::__assume_impl!(@fail $crate::__private::stringify!(let $pat = $expr), $($message)*)
+        };
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_matches {
+    ($expr:expr, [$($pattern:tt)*], $($message:tt)*) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $expr } {
+            $($pattern)* => {}
+            _ => $crate::__assume_impl!(
+                @fail $crate::__private::stringify!(matches!($expr, $($pattern)*)),
+                $($message)*
+            ),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn let_binds_into_scope() {
+        let pair = Some((1, 2));
+        assume_let!(unsafe: Some((first, second)) = pair);
+        assert_eq!(first + second, 3);
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "std", feature = "violation-handler")))]
+    const fn let_can_be_const() {
+        assume_let!(unsafe: Some(value) = Some(1), "impossible");
+        let _ = value;
+    }
+
+    #[test]
+    fn matches_accepts_alternatives_and_guard() {
+        let value = 3;
+        assume_matches!(unsafe: value, 1 | 3);
+        assume_matches!(unsafe: Some(value), Some(x) if x > 2, "guarded by {}", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: let Some(value) = None::<u32>: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn let_reports_pattern() {
+        assume_let!(unsafe: Some(value) = None::<u32>, "oh no");
+        let _ = value;
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: matches!(2, 1 | 3)")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn matches_reports_pattern() {
+        assume_matches!(unsafe: 2, 1 | 3);
+    }
+}
@@ -0,0 +1,287 @@
+//! Pointer assumptions.
+
+/// Assumes that the given pointer is aligned to the given alignment, evaluating to the pointer.
+///
+/// The alignment is in bytes and must be a power of two, which is checked along with the
+/// address. Each operand is evaluated once. In checked configurations a misaligned pointer
+/// panics with its address. Otherwise, the optimizer may rely on the alignment of the pointer
+/// (and of pointers derived from it).
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_aligned;
+///
+/// #[repr(align(64))]
+/// struct Line([u8; 64]);
+///
+/// let line = Line([0; 64]);
+///
+/// // Some pointer that, per invariants, is always cache line aligned.
+/// let ptr: *const u8 = line.0.as_ptr();
+///
+/// let ptr = assume_aligned!(unsafe: ptr, 64);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_aligned {
+    (unsafe: $ptr:expr, $align:expr $(,)?) => {{
+        $crate::__assume_aligned!($ptr, $align, "", "")
+    }};
+    (unsafe: $ptr:expr, $align:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_aligned!($ptr, $align, ": ", $msg)
+    }};
+    (unsafe: $ptr:expr, $align:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_aligned!(
+            $ptr,
+            $align,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a pointer and an alignment");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given pointer is not null, evaluating to it as a [`NonNull`].
+///
+/// The pointer must be a `*mut T`, as with [`NonNull::new`]. In checked configurations a null
+/// pointer panics. Otherwise, this is [`NonNull::new_unchecked`].
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// [`NonNull`]: core::ptr::NonNull
+/// [`NonNull::new`]: core::ptr::NonNull::new
+/// [`NonNull::new_unchecked`]: core::ptr::NonNull::new_unchecked
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_nonnull;
+/// use std::ptr::NonNull;
+///
+/// let mut value = 1;
+///
+/// // Some pointer that, per invariants, is never null.
+/// let ptr: *mut i32 = &mut value;
+///
+/// let ptr: NonNull<i32> = assume_nonnull!(unsafe: ptr, "from a reference");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_nonnull {
+    (unsafe: $ptr:expr $(,)?) => {{
+        $crate::__assume_nonnull!($ptr, "", "")
+    }};
+    (unsafe: $ptr:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_nonnull!($ptr, ": ", $msg)
+    }};
+    (unsafe: $ptr:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_nonnull!($ptr, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a pointer");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given pointer lies within the `len` elements starting at `base`, or one
+/// past the end of them, evaluating to the pointer.
+///
+/// These are the pointers that `base.add(offset)` may produce, so this suits pointer ranges
+/// walked by cursors. The pointer and base must point to the same type. Each operand is
+/// evaluated once. In checked configurations a pointer outside of the range panics with the
+/// addresses involved.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_in_allocation;
+///
+/// let values = [1, 2, 3];
+/// let base = values.as_ptr();
+///
+/// // Some cursor that, per invariants, never leaves the array.
+/// let cursor = base.wrapping_add(2);
+///
+/// let cursor = assume_in_allocation!(unsafe: cursor, base, values.len());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_in_allocation {
+    (unsafe: $ptr:expr, $base:expr, $len:expr $(,)?) => {{
+        $crate::__assume_in_allocation!($ptr, $base, $len, "", "")
+    }};
+    (unsafe: $ptr:expr, $base:expr, $len:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_in_allocation!($ptr, $base, $len, ": ", $msg)
+    }};
+    (unsafe: $ptr:expr, $base:expr, $len:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_in_allocation!(
+            $ptr,
+            $base,
+            $len,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a pointer, a base pointer and a length");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_aligned {
+    ($ptr:expr, $align:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($ptr, $align) } {
+            (ptr, align) => {
+                $crate::__assume_impl!(
+                    @check $crate::__private::is_aligned(ptr, align),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($ptr),
+                        " aligned to ",
+                        $crate::__private::stringify!($align)
+                    ),
+                    "assumption failed: {} aligned to {}{}{}\n  address: {:p}\n    align: {}",
+                    $crate::__private::stringify!($ptr),
+                    $crate::__private::stringify!($align),
+                    $separator,
+                    $msg,
+                    ptr,
+                    align,
+                );
+                ptr
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_nonnull {
+    ($ptr:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match $crate::__private::NonNull::new(unsafe { $ptr }) {
+            $crate::__private::Some(ptr) => ptr,
+            $crate::__private::None => $crate::__assume_impl!(
+                @fail $crate::__private::concat!($crate::__private::stringify!($ptr), " is not null"),
+                "assumption failed: {} is not null{}{}",
+                $crate::__private::stringify!($ptr),
+                $separator,
+                $msg,
+            ),
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_in_allocation {
+    ($ptr:expr, $base:expr, $len:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { ($ptr, $base, $len) } {
+            (ptr, base, len) => {
+                $crate::__assume_impl!(
+                    @check $crate::__private::in_allocation(ptr, base, len),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($ptr),
+                        " in allocation of ",
+                        $crate::__private::stringify!($base)
+                    ),
+                    "assumption failed: {} in allocation of {}{}{}\n  address: {:p}\n     base: {:p}\n      len: {}",
+                    $crate::__private::stringify!($ptr),
+                    $crate::__private::stringify!($base),
+                    $separator,
+                    $msg,
+                    ptr,
+                    base,
+                    len,
+                );
+                ptr
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn __is_aligned<T: ?Sized>(ptr: *const T, align: usize) -> bool {
+    align.is_power_of_two() && (ptr as *const () as usize) & (align - 1) == 0
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn __in_allocation<T>(ptr: *const T, base: *const T, len: usize) -> bool {
+    let address = ptr as usize;
+    let start = base as usize;
+    match len.checked_mul(core::mem::size_of::<T>()) {
+        Some(size) => start <= address && address - start <= size,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_aligned_pointer() {
+        let values = [1u64, 2];
+        let ptr = assume_aligned!(unsafe: values.as_ptr(), 8);
+        assert_eq!(ptr, values.as_ptr());
+        assert_eq!(assume_aligned!(unsafe: &values[..], 1, "oh no").len(), 2);
+    }
+
+    #[test]
+    fn evaluates_to_nonnull() {
+        let mut value = 1;
+        let ptr = assume_nonnull!(unsafe: &mut value as *mut i32, "oh no");
+        assert_eq!(unsafe { *ptr.as_ptr() }, 1);
+    }
+
+    #[test]
+    fn accepts_pointers_up_to_one_past_the_end() {
+        let values = [1, 2, 3];
+        let base = values.as_ptr();
+        assume_in_allocation!(unsafe: base, base, 3);
+        assume_in_allocation!(unsafe: base.wrapping_add(3), base, 3, "oh no");
+        assume_in_allocation!(unsafe: base, base, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: ptr aligned to 3\n  address: ")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn rejects_alignment_that_is_not_a_power_of_two() {
+        use core::ptr;
+
+        let ptr = ptr::null::<u8>();
+        assume_aligned!(unsafe: ptr, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: ptr::null_mut::<u8>() is not null: oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_null() {
+        use core::ptr;
+
+        assume_nonnull!(unsafe: ptr::null_mut::<u8>(), "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: base.wrapping_add(4) in allocation of base")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_pointer_past_the_end() {
+        let values = [1, 2, 3];
+        let base = values.as_ptr();
+        assume_in_allocation!(unsafe: base.wrapping_add(4), base, 3);
+    }
+}
@@ -0,0 +1,107 @@
+//! Range forms of the `assume!` condition: `lo <= x < hi` and `x in lo..hi`.
+//!
+//! These are recognized while `__assume_split!` looks for the message, before the
+//! condition is parsed as an expression, which would otherwise reject them. In both
+//! forms the value is evaluated once, and a failure reports the value and the range.
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_range {
+    // Dispatches on what `__assume_split!` found while splitting off the message.
+    (@done $msg:tt $all:tt [cmp [[$($lo:tt)+] [$($x:tt)+]] [$op1:tt $op2:tt] [$($hi:tt)+]]) => {
+        $crate::__assume_range!(@chain $msg $all [$($lo)+] $op1 [$($x)+] $op2 [$($hi)+])
+    };
+    (@done $msg:tt [$($range:tt)+] [in $in:tt [$($x:tt)+]]) => {
+        $crate::__assume_range!(@in $msg [$($x)+ $in $($range)+] [$($x)+] [$($range)+])
+    };
+    (@done $msg:tt [$($all:tt)*] $_:tt) => {
+        $crate::__assume_range!(@expr $msg [$($all)*])
+    };
+    (@expr ($($msg:tt)*) [$($all:tt)*]) => {
+        $crate::__assume_cond!([$($all)*] [$($all)*] $($msg)*)
+    };
+
+    // `x in range`, for any range type.
+    (@in ($(,)?) $all:tt $x:tt $range:tt) => {
+        $crate::__assume_range!(@in_impl $all $x $range, "", "")
+    };
+    (@in (, $msg:expr $(,)?) $all:tt $x:tt $range:tt) => {
+        $crate::__assume_range!(@in_impl $all $x $range, ": ", $msg)
+    };
+    (@in (, $fmt:expr, $($args:tt)*) $all:tt $x:tt $range:tt) => {
+        $crate::__assume_range!(
+            @in_impl $all $x $range, ": ", $crate::__private::format_args!($fmt, $($args)*)
+        )
+    };
+    (@in_impl [$($all:tt)*] [$($x:tt)+] [$($range:tt)+], $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { (&($($x)+), ($($range)+)) } {
+            (value, range) => {
+                #[allow(unused_imports)]
+                use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                $crate::__assume_impl!(
+                    @check $crate::__private::RangeBounds::contains(&range, value),
+                    $crate::__private::stringify!($($all)*),
+                    "assumption failed: {}{}{}\n  value: {:?}\n  range: {:?}",
+                    $crate::__private::stringify!($($all)*),
+                    $separator,
+                    $msg,
+                    (&$crate::__private::Captured(value)).__assume_value(),
+                    (&$crate::__private::Captured(&range)).__assume_value(),
+                );
+            }
+        }
+    };
+
+    // `lo <= x < hi`, with either comparison being `<` or `<=`.
+    (@chain ($(,)?) $($chain:tt)*) => {
+        $crate::__assume_range!(@chain_impl "", ""; $($chain)*)
+    };
+    (@chain (, $msg:expr $(,)?) $($chain:tt)*) => {
+        $crate::__assume_range!(@chain_impl ": ", $msg; $($chain)*)
+    };
+    (@chain (, $fmt:expr, $($args:tt)*) $($chain:tt)*) => {
+        $crate::__assume_range!(
+            @chain_impl ": ", $crate::__private::format_args!($fmt, $($args)*); $($chain)*
+        )
+    };
+    (
+        @chain_impl $separator:expr, $msg:expr;
+        [$($all:tt)*] [$($lo:tt)+] $op1:tt [$($x:tt)+] $op2:tt [$($hi:tt)+]
+    ) => {
+        #[allow(unused_unsafe)]
+        match unsafe { (&($($lo)+), &($($x)+), &($($hi)+)) } {
+            (lo, value, hi) => {
+                #[allow(unused_imports)]
+                use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                $crate::__assume_impl!(
+                    @check *lo $op1 *value && *value $op2 *hi,
+                    $crate::__private::stringify!($($all)*),
+                    "assumption failed: {}{}{}\n  value: {:?}\n  range: {}{:?}, {:?}{}",
+                    $crate::__private::stringify!($($all)*),
+                    $separator,
+                    $msg,
+                    (&$crate::__private::Captured(value)).__assume_value(),
+                    $crate::__assume_range!(@open $op1),
+                    (&$crate::__private::Captured(lo)).__assume_value(),
+                    (&$crate::__private::Captured(hi)).__assume_value(),
+                    $crate::__assume_range!(@close $op2),
+                );
+            }
+        }
+    };
+    (@open <) => {
+        "("
+    };
+    (@open <=) => {
+        "["
+    };
+    (@close <) => {
+        ")"
+    };
+    (@close <=) => {
+        "]"
+    };
+}
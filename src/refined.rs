@@ -0,0 +1,327 @@
+//! Values that carry an assumption in their type.
+
+use core::any::type_name;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use __private::assume_unchecked;
+
+/// A condition on values of type `T`, for use with [`Refined`].
+///
+/// # Safety
+/// `test` must return the same result for the same value, every time. [`Refined::get`] hands
+/// the result to the optimizer, so a predicate that does not (e.g. one reading a global, or
+/// the contents of a `Cell`) is undefined behavior.
+///
+/// ```
+/// use assume::{Predicate, Refined};
+///
+/// struct Even;
+///
+/// unsafe impl Predicate<u32> for Even {
+///     fn test(value: &u32) -> bool {
+///         value.is_multiple_of(2)
+///     }
+/// }
+///
+/// let half = |n: Refined<u32, Even>| *n.get() / 2;
+/// assert_eq!(half(Refined::new(10).unwrap()), 5);
+/// ```
+pub unsafe trait Predicate<T: ?Sized> {
+    /// Returns whether the value satisfies the predicate.
+    fn test(value: &T) -> bool;
+
+    /// Whether to hand `test` to the optimizer at every use of a refined value.
+    ///
+    /// As with `assume!`, an unchecked assumption is still evaluated. Predicates the optimizer
+    /// cannot make use of, such as ones that loop over a collection, should turn this off.
+    const HINT: bool = true;
+}
+
+/// A value of type `T` that satisfies the predicate `P`.
+///
+/// The predicate is stated once, when the value is refined, rather than with `assume!` at each
+/// use: every access through [`get`] (or `Deref`) assumes it again, so that the optimizer sees
+/// it wherever the value is used, including in other crates after inlining.
+///
+/// [`get`]: Refined::get
+///
+/// ```
+/// use assume::{Lt, Refined};
+///
+/// fn lookup(table: &[u32; 16], index: Refined<usize, Lt<16>>) -> u32 {
+///     table[*index.get()]  // Bounds check optimized out per assumption.
+/// }
+///
+/// let index = Refined::new(3).expect("index out of range");
+/// assert_eq!(lookup(&[7; 16], index), 7);
+/// ```
+pub struct Refined<T, P: Predicate<T>> {
+    value: T,
+    predicate: PhantomData<fn() -> P>,
+}
+
+impl<T, P: Predicate<T>> Refined<T, P> {
+    /// Refines the value if it satisfies the predicate, and gives it back otherwise.
+    #[inline]
+    pub fn new(value: T) -> Result<Self, T> {
+        if P::test(&value) {
+            Ok(Refined {
+                value,
+                predicate: PhantomData,
+            })
+        } else {
+            Err(value)
+        }
+    }
+
+    /// Refines the value, assuming that it satisfies the predicate.
+    ///
+    /// In checked configurations a value that does not satisfy it panics, naming the
+    /// predicate.
+    ///
+    /// # Safety
+    /// The value must satisfy the predicate.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn new_unchecked(value: T) -> Self {
+        if ::__assume_checked!() && !P::test(&value) {
+            ::__assume_impl!(
+                @fail type_name::<P>(),
+                "assumption failed: value satisfies {}",
+                type_name::<P>(),
+            );
+        }
+
+        Refined {
+            value,
+            predicate: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the value, assuming the predicate.
+    #[inline(always)]
+    pub fn get(&self) -> &T {
+        self.hint();
+        &self.value
+    }
+
+    /// Returns the value, assuming the predicate.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.hint();
+        self.value
+    }
+
+    #[inline(always)]
+    fn hint(&self) {
+        if P::HINT && !::__assume_checked!() {
+            // Safe, as refined values satisfy the predicate by construction.
+            unsafe { assume_unchecked(P::test(&self.value)) }
+        }
+    }
+}
+
+impl<T, P: Predicate<T>> Deref for Refined<T, P> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: Clone, P: Predicate<T>> Clone for Refined<T, P> {
+    fn clone(&self) -> Self {
+        Refined {
+            value: self.value.clone(),
+            predicate: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, P: Predicate<T>> Copy for Refined<T, P> {}
+
+impl<T: fmt::Debug, P: Predicate<T>> fmt::Debug for Refined<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: PartialEq, P: Predicate<T>> PartialEq for Refined<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, P: Predicate<T>> Eq for Refined<T, P> {}
+
+impl<T: PartialOrd, P: Predicate<T>> PartialOrd for Refined<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord, P: Predicate<T>> Ord for Refined<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash, P: Predicate<T>> Hash for Refined<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+/// Integers less than `N`.
+pub struct Lt<const N: i128>;
+
+/// Integers from `A` to `B`, inclusive.
+pub struct InRange<const A: i128, const B: i128>;
+
+/// Collections with at least one element.
+pub struct NonEmpty;
+
+/// Collections whose elements are in ascending order.
+///
+/// The optimizer cannot make use of this, so it is checked but never handed to it.
+pub struct Sorted;
+
+macro_rules! integer_predicates {
+    ($($ty:ty)*) => {$(
+        unsafe impl<const N: i128> Predicate<$ty> for Lt<N> {
+            #[inline(always)]
+            fn test(value: &$ty) -> bool {
+                (*value as i128) < N
+            }
+        }
+
+        unsafe impl<const A: i128, const B: i128> Predicate<$ty> for InRange<A, B> {
+            #[inline(always)]
+            fn test(value: &$ty) -> bool {
+                A <= *value as i128 && *value as i128 <= B
+            }
+        }
+    )*};
+}
+
+// Not `u128`, whose values do not all fit in the bounds.
+integer_predicates!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 usize);
+
+unsafe impl<'a, T> Predicate<&'a [T]> for NonEmpty {
+    #[inline(always)]
+    fn test(value: &&'a [T]) -> bool {
+        !value.is_empty()
+    }
+}
+
+unsafe impl<'a> Predicate<&'a str> for NonEmpty {
+    #[inline(always)]
+    fn test(value: &&'a str) -> bool {
+        !value.is_empty()
+    }
+}
+
+unsafe impl<T, const N: usize> Predicate<[T; N]> for NonEmpty {
+    #[inline(always)]
+    fn test(_: &[T; N]) -> bool {
+        N != 0
+    }
+}
+
+unsafe impl<'a, T: PartialOrd> Predicate<&'a [T]> for Sorted {
+    fn test(value: &&'a [T]) -> bool {
+        value.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    const HINT: bool = false;
+}
+
+unsafe impl<T: PartialOrd, const N: usize> Predicate<[T; N]> for Sorted {
+    fn test(value: &[T; N]) -> bool {
+        value.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    const HINT: bool = false;
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use super::{NonEmpty, Predicate, Sorted};
+    use std::string::String;
+    use std::vec::Vec;
+
+    unsafe impl<T> Predicate<Vec<T>> for NonEmpty {
+        #[inline(always)]
+        fn test(value: &Vec<T>) -> bool {
+            !value.is_empty()
+        }
+    }
+
+    unsafe impl Predicate<String> for NonEmpty {
+        #[inline(always)]
+        fn test(value: &String) -> bool {
+            !value.is_empty()
+        }
+    }
+
+    unsafe impl<T: PartialOrd> Predicate<Vec<T>> for Sorted {
+        fn test(value: &Vec<T>) -> bool {
+            value.windows(2).all(|pair| pair[0] <= pair[1])
+        }
+
+        const HINT: bool = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {InRange, Lt, NonEmpty, Predicate, Refined, Sorted};
+
+    struct Even;
+
+    unsafe impl Predicate<u32> for Even {
+        fn test(value: &u32) -> bool {
+            value.is_multiple_of(2)
+        }
+    }
+
+    #[test]
+    fn refines_values() {
+        let index: Refined<usize, Lt<16>> = Refined::new(15).unwrap();
+        assert_eq!(*index.get(), 15);
+        assert_eq!(Refined::<usize, Lt<16>>::new(16), Err(16));
+
+        let offset: Refined<i8, InRange<-3, 3>> = Refined::new(-3).unwrap();
+        assert_eq!(offset.into_inner(), -3);
+        assert!(Refined::<i8, InRange<-3, 3>>::new(4).is_err());
+
+        let word: Refined<&str, NonEmpty> = Refined::new("hi").unwrap();
+        assert_eq!(word.len(), 2);
+        assert!(Refined::<&str, NonEmpty>::new("").is_err());
+
+        assert!(Refined::<&[u8], Sorted>::new(&[1, 2, 2]).is_ok());
+        assert!(Refined::<[u8; 2], Sorted>::new([2, 1]).is_err());
+
+        let even: Refined<u32, Even> = Refined::new(4).unwrap();
+        assert_eq!(*even / 2, 2);
+        assert!(Refined::<u32, Even>::new(3).is_err());
+    }
+
+    #[test]
+    fn assumes_values() {
+        let index: Refined<u64, Lt<16>> = unsafe { Refined::new_unchecked(15) };
+        assert_eq!(index, Refined::new(15).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: value satisfies assume::refined::Lt<16>")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_unsatisfied_predicate() {
+        let _: Refined<u64, Lt<16>> = unsafe { Refined::new_unchecked(16) };
+    }
+}
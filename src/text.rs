@@ -0,0 +1,189 @@
+//! Text assumptions.
+
+/// Assumes that the given bytes are valid UTF-8, evaluating to them as a `&str`.
+///
+/// The bytes can be anything that can be sliced into a `&[u8]`, such as a `Vec<u8>` or an
+/// array. In checked configurations they are validated with [`str::from_utf8`], and invalid
+/// UTF-8 panics with the error. Otherwise, this is [`str::from_utf8_unchecked`].
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// [`str::from_utf8`]: core::str::from_utf8
+/// [`str::from_utf8_unchecked`]: core::str::from_utf8_unchecked
+///
+/// ```
+/// # fn read_validated() -> Vec<u8> { b"key=value".to_vec() }
+/// # fn main() {
+/// use assume::assume_utf8;
+///
+/// // Some buffer that, per invariants, was validated upstream.
+/// let buffer = read_validated();
+///
+/// let text: &str = assume_utf8!(unsafe: buffer, "validated on input");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_utf8 {
+    (unsafe: $bytes:expr $(,)?) => {{
+        $crate::__assume_utf8!($bytes, "", "")
+    }};
+    (unsafe: $bytes:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_utf8!($bytes, ": ", $msg)
+    }};
+    (unsafe: $bytes:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_utf8!($bytes, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be bytes");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given byte index is a char boundary of the given string, evaluating to the
+/// string split at that index.
+///
+/// This is [`str::split_at`] without the check. Each operand is evaluated once. In checked
+/// configurations an index that is not on a char boundary, or that is beyond the end of the
+/// string, panics with the index and length. Otherwise, the halves are taken with
+/// `get_unchecked`.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn find_separator(_: &str) -> usize { 3 }
+/// # fn main() {
+/// use assume::assume_char_boundary;
+///
+/// let line = "key=value";
+///
+/// // Some index that, per invariants, is always at an ASCII separator.
+/// let i = find_separator(line);
+///
+/// let (key, rest) = assume_char_boundary!(unsafe: line, i);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_char_boundary {
+    (unsafe: $str:expr, $index:expr $(,)?) => {{
+        $crate::__assume_char_boundary!($str, $index, "", "")
+    }};
+    (unsafe: $str:expr, $index:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_char_boundary!($str, $index, ": ", $msg)
+    }};
+    (unsafe: $str:expr, $index:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_char_boundary!(
+            $str,
+            $index,
+            ": ",
+            $crate::__private::format_args!($fmt, $($args)*)
+        )
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be a string and an index");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_utf8 {
+    ($bytes:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { &$bytes[..] } {
+            bytes => {
+                if $crate::__assume_checked!() {
+                    match $crate::__private::from_utf8(bytes) {
+                        $crate::__private::Ok(text) => text,
+                        $crate::__private::Err(error) => $crate::__assume_impl!(
+                            @fail $crate::__private::concat!(
+                                $crate::__private::stringify!($bytes),
+                                " is UTF-8"
+                            ),
+                            "assumption failed: {} is UTF-8{}{}\n  error: {:?}",
+                            $crate::__private::stringify!($bytes),
+                            $separator,
+                            $msg,
+                            error,
+                        ),
+                    }
+                } else {
+                    unsafe { $crate::__private::from_utf8_unchecked(bytes) }
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_char_boundary {
+    ($str:expr, $index:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { (&$str[..], $index) } {
+            (text, index) => {
+                $crate::__assume_impl!(
+                    @check text.is_char_boundary(index),
+                    $crate::__private::concat!(
+                        $crate::__private::stringify!($index),
+                        " is a char boundary of ",
+                        $crate::__private::stringify!($str)
+                    ),
+                    "assumption failed: {} is a char boundary of {}{}{}\n  index: {}\n    len: {}",
+                    $crate::__private::stringify!($index),
+                    $crate::__private::stringify!($str),
+                    $separator,
+                    $msg,
+                    index,
+                    text.len(),
+                );
+                unsafe { (text.get_unchecked(..index), text.get_unchecked(index..)) }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_str() {
+        let bytes = [b'h', b'i'];
+        assert_eq!(assume_utf8!(unsafe: bytes), "hi");
+        assert_eq!(assume_utf8!(unsafe: "é".as_bytes(), "oh no"), "é");
+        assert_eq!(
+            assume_utf8!(unsafe: &bytes[1..], "oh no, a {}", "problem"),
+            "i"
+        );
+    }
+
+    #[test]
+    fn evaluates_to_halves() {
+        let text = "aé";
+        assert_eq!(assume_char_boundary!(unsafe: text, 1), ("a", "é"));
+        assert_eq!(assume_char_boundary!(unsafe: text, 0, "oh no"), ("", "aé"));
+        assert_eq!(assume_char_boundary!(unsafe: text, text.len()), ("aé", ""));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: bytes is UTF-8: oh no\n  error: Utf8Error { valid_up_to: 1"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_invalid_utf8() {
+        let bytes = [b'a', 0xff];
+        assume_utf8!(unsafe: bytes, "oh no");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assumption failed: 1 + 1 is a char boundary of text\n  index: 2\n    len: 3"
+    )]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn reports_index_inside_char() {
+        let text = "aé";
+        assume_char_boundary!(unsafe: text, 1 + 1);
+    }
+}
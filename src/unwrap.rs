@@ -0,0 +1,164 @@
+//! Value-returning assumptions about `Option` and `Result`.
+
+/// Assumes that the given `Option` is `Some`, evaluating to the contained value.
+///
+/// In checked configurations `None` panics with a descriptive message. Otherwise, this is
+/// [`Option::unwrap_unchecked`], and the panic path of a later `unwrap()` is not needed at all.
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # fn populate_items() -> HashMap<u32, String> {
+/// #     let mut result = HashMap::default();
+/// #     result.insert(0, "hello".to_string());
+/// #     result
+/// # }
+/// # fn main() {
+/// use assume::assume_some;
+///
+/// let items: HashMap<u32, String> = populate_items();
+///
+/// // Some item that, per invariants, always exists.
+/// let item_zero: &String = assume_some!(
+///     unsafe: items.get(&0),
+///     "item zero missing from items map",
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assume_some {
+    (unsafe: $option:expr $(,)?) => {{
+        $crate::__assume_some!($option, "", "")
+    }};
+    (unsafe: $option:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_some!($option, ": ", $msg)
+    }};
+    (unsafe: $option:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_some!($option, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be an expression");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+/// Assumes that the given `Result` is `Ok`, evaluating to the contained value.
+///
+/// In checked configurations `Err` panics with a descriptive message, including the error if
+/// it implements [`Debug`]. Otherwise, this is [`Result::unwrap_unchecked`].
+///
+/// Accepts the same optional message as `assume!`.
+///
+/// ```
+/// # fn main() {
+/// use assume::assume_ok;
+///
+/// // Some text that, per invariants, is always a number.
+/// let text = "42";
+///
+/// let number: u32 = assume_ok!(unsafe: text.parse(), "text {:?} is not a number", text);
+/// # }
+/// ```
+///
+/// [`Debug`]: core::fmt::Debug
+#[macro_export]
+macro_rules! assume_ok {
+    (unsafe: $result:expr $(,)?) => {{
+        $crate::__assume_ok!($result, "", "")
+    }};
+    (unsafe: $result:expr, $msg:expr $(,)?) => {{
+        $crate::__assume_ok!($result, ": ", $msg)
+    }};
+    (unsafe: $result:expr, $fmt:expr, $($args:tt)*) => {{
+        $crate::__assume_ok!($result, ": ", $crate::__private::format_args!($fmt, $($args)*))
+    }};
+    (unsafe: $($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be an expression");
+    }};
+    ($($_:tt)*) => {{
+        $crate::__private::compile_error!("assumption must be prefixed with 'unsafe: '");
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_some {
+    ($option:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $option } {
+            $crate::__private::Some(value) => value,
+            $crate::__private::None => $crate::__assume_impl!(
+                @fail $crate::__private::concat!($crate::__private::stringify!($option), ".is_some()"),
+                "assumption failed: {}.is_some(){}{}",
+                $crate::__private::stringify!($option),
+                $separator,
+                $msg,
+            ),
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __assume_ok {
+    ($result:expr, $separator:expr, $msg:expr) => {
+        #[allow(unused_unsafe)]
+        match unsafe { $result } {
+            $crate::__private::Ok(value) => value,
+            $crate::__private::Err(error) => {
+                #[allow(unused_imports)]
+                use $crate::__private::{CapturedDebug as _, CapturedOpaque as _};
+
+                $crate::__assume_impl!(
+                    @fail $crate::__private::concat!($crate::__private::stringify!($result), ".is_ok()"),
+                    "assumption failed: {}.is_ok(){}{}\n  error: {:?}",
+                    $crate::__private::stringify!($result),
+                    $separator,
+                    $msg,
+                    (&$crate::__private::Captured(&error)).__assume_value(),
+                )
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluates_to_value() {
+        assert_eq!(assume_some!(unsafe: Some(1)), 1);
+        assert_eq!(assume_ok!(unsafe: "2".parse::<u32>(), "not a number"), 2);
+    }
+
+    #[test]
+    fn can_be_unsafe() {
+        let values = [Some(1)];
+        assert_eq!(assume_some!(unsafe: *values.get_unchecked(0)), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: None::<u32>.is_some(): oh no")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn some_reports_expression() {
+        assume_some!(unsafe: None::<u32>, "oh no");
+    }
+
+    #[test]
+    #[should_panic(expected = "assumption failed: Err::<u32, _>(3).is_ok()\n  error: 3")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn ok_reports_error() {
+        assume_ok!(unsafe: Err::<u32, _>(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "error: <not Debug>")]
+    #[cfg(all(assume_checks, not(feature = "std")))]
+    fn ok_reports_error_without_debug() {
+        struct NotDebug;
+
+        assume_ok!(unsafe: Err::<u32, _>(NotDebug));
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(any(feature = "always-check", assume_checked))]
+
+#[macro_use]
+extern crate assume;
+
+// The policy applies to every crate using `assume!`, in every profile, so these run in a
+// crate of their own and are not gated on `debug_assertions`.
+
+#[test]
+fn checks_in_every_profile() {
+    assume!(unsafe: 1 > 0);
+    assert_eq!(assume_not_nan!(unsafe: 0.5f64), 0.5);
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: 2 > 3: checked in every profile")]
+#[cfg(not(feature = "std"))]
+fn reports_violation() {
+    assume!(unsafe: 2 > 3, "checked in every profile");
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: unreachable: checked in every profile")]
+#[cfg(not(feature = "std"))]
+fn reports_unreachable() {
+    assume!(unsafe: @unreachable, "checked in every profile");
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: value satisfies assume::refined::Lt<4>")]
+#[cfg(not(feature = "std"))]
+fn reports_violation_within_assume() {
+    let _: assume::Refined<u8, assume::Lt<4>> = unsafe { assume::Refined::new_unchecked(4) };
+}
@@ -0,0 +1,230 @@
+#![cfg(feature = "contracts")]
+
+extern crate assume;
+
+use std::convert::TryFrom;
+use std::num::ParseIntError;
+use std::ops::ControlFlow;
+use std::task::Poll;
+
+use assume::{ensures, requires};
+
+#[requires(index < 4)]
+#[requires(table[index] != 0)]
+fn divide(table: &[u32; 4], index: usize, value: u32) -> u32 {
+    value / table[index]
+}
+
+#[ensures(|ret: &u32| *ret < 12)]
+fn month(index: u32) -> u32 {
+    if index == 0 {
+        return 11;
+    }
+    index - 1
+}
+
+#[ensures(|ret| ret.is_err() || *ret == Ok(byte))]
+fn parse(text: &str, byte: u8) -> Result<u8, std::num::ParseIntError> {
+    let value = text.parse()?;
+    Ok(value)
+}
+
+#[ensures(|ret| ret.len() <= limit)]
+fn first(text: &str, limit: usize) -> &str {
+    &text[..limit.min(text.len())]
+}
+
+#[ensures(|ret| ret.clone().count() == 2)]
+fn pair() -> impl Iterator<Item = u32> + Clone {
+    0..2
+}
+
+#[ensures(|ret| ret.as_ref().is_none_or(|value| **value == 1))]
+fn find_one(values: &mut [u32]) -> Option<&mut u32> {
+    let index = values.iter().position(|&value| value == 1)?;
+    Some(&mut values[index])
+}
+
+#[ensures(|ret| ret.is_some())]
+fn head(values: &[u32]) -> Option<u32> {
+    let value = values.first()?;
+    Some(*value)
+}
+
+#[ensures(|ret| ret.is_ok())]
+fn describe(values: &[u32]) -> Result<String, std::num::TryFromIntError> {
+    let first = 'first: {
+        for &value in values {
+            if value != 0 {
+                break 'first value;
+            }
+        }
+        return Ok(String::new());
+    };
+    let evens = values
+        .iter()
+        .filter(|&&value| {
+            if value == 0 {
+                return false;
+            }
+            value.is_multiple_of(2)
+        })
+        .count();
+    Ok(format!("{}{}", u8::try_from(first)?, evens))
+}
+
+#[ensures(|ret| ret.as_ref().is_none_or(|values| values.len() == count))]
+fn repeat(value: Option<u32>, count: usize) -> Option<Vec<u32>> {
+    Some(vec![value?; count])
+}
+
+#[ensures(|ret| ret.continue_value().is_none_or(|value| value < u32::MAX))]
+fn countdown(from: ControlFlow<u32, u32>) -> ControlFlow<u32, u32> {
+    let from = from?;
+    if from == 0 {
+        return ControlFlow::Break(0);
+    }
+    ControlFlow::Continue(from - 1)
+}
+
+#[ensures(|ret| !matches!(*ret, Poll::Ready(Ok(0))))]
+fn poll_parse(text: Poll<Result<&str, ParseIntError>>) -> Poll<Result<u8, ParseIntError>> {
+    let byte: u8 = match text? {
+        Poll::Ready(text) => text.parse()?,
+        Poll::Pending => return Poll::Pending,
+    };
+    Poll::Ready(Ok(byte.max(1)))
+}
+
+#[ensures(|ret| ret.is_pending() || ret.is_ready())]
+fn poll_next(item: Poll<Option<Result<u8, u8>>>) -> Poll<Option<Result<u32, u32>>> {
+    match item? {
+        Poll::Ready(value) => Poll::Ready(value.map(|value| Ok(u32::from(value)))),
+        Poll::Pending => Poll::Pending,
+    }
+}
+
+struct Counter(u32);
+
+impl Counter {
+    #[requires(self.0 < 10)]
+    #[ensures(|ret| *ret == self.0)]
+    fn increment(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+
+    #[ensures(|ret| **ret == 0)]
+    fn reset(&mut self) -> &mut u32 {
+        self.0 = 0;
+        &mut self.0
+    }
+}
+
+#[test]
+fn preconditions_pass_through() {
+    assert_eq!(divide(&[1, 2, 3, 4], 1, 10), 5);
+}
+
+#[test]
+fn postconditions_pass_through() {
+    assert_eq!(month(0), 11);
+    assert_eq!(month(12), 11);
+    assert_eq!(first("hello", 2), "he");
+    assert_eq!(pair().sum::<u32>(), 1);
+
+    let mut counter = Counter(0);
+    assert_eq!(counter.increment(), 1);
+    assert_eq!(counter.0, 1);
+    *counter.reset() += 2;
+    assert_eq!(counter.0, 2);
+}
+
+#[test]
+fn postconditions_apply_to_every_exit() {
+    let mut values = [0, 1];
+    *find_one(&mut values).unwrap() = 3;
+    assert_eq!(values, [0, 3]);
+    assert_eq!(find_one(&mut values), None);
+
+    assert_eq!(head(&[4]), Some(4));
+    assert_eq!(describe(&[0, 3, 2, 4]), Ok("32".to_string()));
+    assert_eq!(describe(&[0]), Ok(String::new()));
+}
+
+#[test]
+fn question_mark_applies_to_every_std_try_type() {
+    assert_eq!(repeat(Some(2), 3), Some(vec![2, 2, 2]));
+    assert_eq!(repeat(None, 3), None);
+
+    assert_eq!(
+        countdown(ControlFlow::Continue(2)),
+        ControlFlow::Continue(1)
+    );
+    assert_eq!(countdown(ControlFlow::Continue(0)), ControlFlow::Break(0));
+    assert_eq!(countdown(ControlFlow::Break(5)), ControlFlow::Break(5));
+
+    let error = "x".parse::<u8>().unwrap_err();
+    assert_eq!(poll_parse(Poll::Ready(Ok("7"))), Poll::Ready(Ok(7)));
+    assert_eq!(
+        poll_parse(Poll::Ready(Ok("x"))),
+        Poll::Ready(Err(error.clone()))
+    );
+    assert_eq!(
+        poll_parse(Poll::Ready(Err(error.clone()))),
+        Poll::Ready(Err(error))
+    );
+    assert_eq!(poll_parse(Poll::Pending), Poll::Pending);
+
+    assert_eq!(
+        poll_next(Poll::Ready(Some(Ok(3)))),
+        Poll::Ready(Some(Ok(3)))
+    );
+    assert_eq!(
+        poll_next(Poll::Ready(Some(Err(4)))),
+        Poll::Ready(Some(Err(4)))
+    );
+    assert_eq!(poll_next(Poll::Ready(None)), Poll::Ready(None));
+    assert_eq!(poll_next(Poll::Pending), Poll::Pending);
+}
+
+#[test]
+fn postconditions_apply_to_ok_values() {
+    assert_eq!(parse("7", 7), Ok(7));
+    assert!(parse("x", 7).is_err());
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: index < 4: precondition of `divide`")]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_precondition() {
+    divide(&[1, 2, 3, 4], 4, 10);
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: table[index] != 0: precondition of `divide`")]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_failing_clause() {
+    divide(&[1, 0, 3, 4], 1, 10);
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: *ret < 12: postcondition of `month`")]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_postcondition() {
+    month(13);
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: ret.is_some(): postcondition of `head`")]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_postcondition_of_early_return() {
+    head(&[]);
+}
+
+#[test]
+#[should_panic(expected = "assumption failed: ret.is_ok(): postcondition of `describe`")]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_postcondition_of_error_in_macro() {
+    describe(&[300]).ok();
+}
@@ -0,0 +1,60 @@
+#![cfg(all(feature = "unchecked-in-debug", debug_assertions))]
+
+// Builds `examples/debug_hints.rs` as in its instructions, in a target directory of its own so
+// as not to wait on the build running this test, and checks the code generated for it.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// The instructions of the function `name` in the assembly `asm`.
+fn function<'a>(asm: &'a str, name: &str) -> Vec<&'a str> {
+    let label = std::format!("{}:", name);
+    let mangled = std::format!("_{}:", name);
+    asm.lines()
+        .skip_while(|line| *line != label && *line != mangled)
+        .skip(1)
+        .take_while(|line| !line.contains(".cfi_endproc") && !line.starts_with(".Lfunc_end"))
+        .filter(|line| !line.trim_start().starts_with('.'))
+        .collect()
+}
+
+#[test]
+fn hints_apply_at_opt_level_1() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("debug_hints");
+    let status = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args([
+            "rustc",
+            "--features",
+            "unchecked-in-debug",
+            "--example",
+            "debug_hints",
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "-C", "opt-level=1", "--emit=asm"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let examples = target_dir.join("debug").join("examples");
+    let asm = fs::read_dir(&examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|extension| extension == "s"))
+        .map(|path| fs::read_to_string(path).unwrap())
+        .unwrap();
+
+    let get = function(&asm, "get");
+    let get_checked = function(&asm, "get_checked");
+    let slot = function(&asm, "slot");
+    assert!(!get.is_empty() && !get_checked.is_empty() && !slot.is_empty());
+
+    assert!(!get.iter().any(|line| line.contains("panic_bounds_check")));
+    assert!(get_checked
+        .iter()
+        .any(|line| line.contains("panic_bounds_check")));
+    assert!(!slot.iter().any(|line| line.contains("div")));
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "contracts")]
+
+extern crate assume;
+
+use assume::{maintains_invariant, Invariant};
+
+#[derive(Invariant)]
+#[invariant(self.evens.iter().all(|&i| i < self.values.len()))]
+#[invariant(self.evens.len() <= self.values.len())]
+struct ValuesWithEvens {
+    values: Vec<u32>,
+    evens: Vec<usize>,
+}
+
+impl ValuesWithEvens {
+    #[maintains_invariant]
+    fn push(&mut self, value: u32) {
+        if value.is_multiple_of(2) {
+            self.evens.push(self.values.len());
+        }
+        self.values.push(value);
+    }
+
+    #[maintains_invariant]
+    fn pop_even(&mut self) -> Option<u32> {
+        let index = self.evens.pop()?;
+        Some(self.values[index])
+    }
+
+    #[maintains_invariant]
+    fn truncate_values(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    #[maintains_invariant]
+    fn copy_evens<'a>(&self, out: &'a mut Vec<u32>) -> &'a mut [u32] {
+        let start = out.len();
+        out.extend(self.evens.iter().map(|&index| self.values[index]));
+        &mut out[start..]
+    }
+}
+
+#[derive(Invariant)]
+#[invariant(self.0 <= self.1)]
+struct Span<T: PartialOrd>(T, T);
+
+#[derive(Invariant)]
+struct Anything;
+
+#[test]
+fn derives_invariant() {
+    assert!(Span(1, 2).holds());
+    assert!(!Span(2.0, 1.0).holds());
+    assert!(Anything.holds());
+
+    let broken = ValuesWithEvens {
+        values: vec![2],
+        evens: vec![0, 1],
+    };
+    assert!(!broken.holds());
+}
+
+#[test]
+fn maintains_invariant() {
+    let mut vwe = ValuesWithEvens {
+        values: Vec::new(),
+        evens: Vec::new(),
+    };
+    vwe.push(1);
+    vwe.push(2);
+    vwe.push(4);
+    let mut out = vec![0];
+    vwe.copy_evens(&mut out)[1] += 1;
+    assert_eq!(out, [0, 2, 5]);
+    assert_eq!(vwe.pop_even(), Some(4));
+    assert_eq!(vwe.pop_even(), Some(2));
+    assert_eq!(vwe.pop_even(), None);
+    assert!(vwe.holds());
+}
+
+#[test]
+#[should_panic(
+    expected = "assumption failed: self holds its invariant: on exit from `truncate_values`\n  \
+                   clause: self.evens.iter().all(|&i| i < self.values.len())"
+)]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_broken_invariant() {
+    let mut vwe = ValuesWithEvens {
+        values: Vec::new(),
+        evens: Vec::new(),
+    };
+    vwe.push(1);
+    vwe.push(2);
+    vwe.truncate_values(1);
+}
+
+#[test]
+#[should_panic(
+    expected = "assumption failed: self holds its invariant: on entry to `pop_even`\n  \
+                   clause: self.evens.len() <= self.values.len()"
+)]
+#[cfg(all(assume_checks, not(feature = "std")))]
+fn reports_failing_clause() {
+    let mut vwe = ValuesWithEvens {
+        values: vec![2],
+        evens: vec![0, 0],
+    };
+    vwe.pop_even();
+}